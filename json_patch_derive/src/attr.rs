@@ -0,0 +1,206 @@
+use syn::meta::ParseNestedMeta;
+use syn::Attribute;
+use syn::Error;
+use syn::LitStr;
+use syn::Result;
+use syn::Token;
+
+/// Serde attributes of a struct or enum.
+#[derive(Default)]
+pub struct Container {
+  pub rename_all: Option<Case>,
+  pub transparent: bool,
+  pub tagged: bool,
+}
+
+impl Container {
+  pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+    let mut this: Self = Self::default();
+
+    parse_serde(attrs, |meta| {
+      if meta.path.is_ident("rename_all") {
+        this.rename_all = parse_rename(&meta)?
+          .map(|rule| Case::parse(&rule))
+          .transpose()?;
+      } else if meta.path.is_ident("transparent") {
+        this.transparent = true;
+      } else if meta.path.is_ident("tag")
+        || meta.path.is_ident("content")
+        || meta.path.is_ident("untagged")
+      {
+        this.tagged = true;
+        skip(meta)?;
+      } else {
+        skip(meta)?;
+      }
+
+      Ok(())
+    })?;
+
+    Ok(this)
+  }
+}
+
+/// Serde attributes of a field or enum variant.
+#[derive(Default)]
+pub struct Member {
+  pub rename: Option<LitStr>,
+  pub rename_all: Option<Case>,
+  pub skip: bool,
+  pub flatten: bool,
+}
+
+impl Member {
+  pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+    let mut this: Self = Self::default();
+
+    parse_serde(attrs, |meta| {
+      if meta.path.is_ident("rename") {
+        this.rename = parse_rename(&meta)?;
+      } else if meta.path.is_ident("rename_all") {
+        this.rename_all = parse_rename(&meta)?
+          .map(|rule| Case::parse(&rule))
+          .transpose()?;
+      } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+        this.skip = true;
+      } else if meta.path.is_ident("flatten") {
+        this.flatten = true;
+      } else {
+        skip(meta)?;
+      }
+
+      Ok(())
+    })?;
+
+    Ok(this)
+  }
+}
+
+/// A serde `rename_all` rule.
+#[derive(Clone, Copy)]
+pub enum Case {
+  Lower,
+  Upper,
+  Pascal,
+  Camel,
+  Snake,
+  ScreamingSnake,
+  Kebab,
+  ScreamingKebab,
+}
+
+impl Case {
+  fn parse(rule: &LitStr) -> Result<Self> {
+    match rule.value().as_str() {
+      "lowercase" => Ok(Self::Lower),
+      "UPPERCASE" => Ok(Self::Upper),
+      "PascalCase" => Ok(Self::Pascal),
+      "camelCase" => Ok(Self::Camel),
+      "snake_case" => Ok(Self::Snake),
+      "SCREAMING_SNAKE_CASE" => Ok(Self::ScreamingSnake),
+      "kebab-case" => Ok(Self::Kebab),
+      "SCREAMING-KEBAB-CASE" => Ok(Self::ScreamingKebab),
+      _ => Err(Error::new_spanned(rule, "unknown rename rule")),
+    }
+  }
+
+  /// Applies the rule to a `snake_case` field name.
+  pub fn field(self, name: &str) -> String {
+    match self {
+      Self::Lower | Self::Snake => name.into(),
+      Self::Upper | Self::ScreamingSnake => name.to_ascii_uppercase(),
+      Self::Pascal => name.split('_').map(capitalize).collect(),
+      Self::Camel => {
+        let pascal: String = Self::Pascal.field(name);
+        let mut chars = pascal.chars();
+
+        chars
+          .next()
+          .map(|char| char.to_ascii_lowercase().to_string() + chars.as_str())
+          .unwrap_or_default()
+      }
+      Self::Kebab => name.replace('_', "-"),
+      Self::ScreamingKebab => name.to_ascii_uppercase().replace('_', "-"),
+    }
+  }
+
+  /// Applies the rule to a `PascalCase` variant name.
+  pub fn variant(self, name: &str) -> String {
+    match self {
+      Self::Lower => name.to_ascii_lowercase(),
+      Self::Upper => name.to_ascii_uppercase(),
+      Self::Pascal => name.into(),
+      Self::Camel => name[..1].to_ascii_lowercase() + &name[1..],
+      Self::Snake | Self::ScreamingSnake | Self::Kebab | Self::ScreamingKebab => {
+        let mut snake: String = String::new();
+
+        for (index, char) in name.char_indices() {
+          if char.is_uppercase() && index != 0 {
+            snake.push('_');
+          }
+
+          snake.push(char.to_ascii_lowercase());
+        }
+
+        match self {
+          Self::ScreamingSnake => snake.to_ascii_uppercase(),
+          Self::Kebab => snake.replace('_', "-"),
+          Self::ScreamingKebab => snake.to_ascii_uppercase().replace('_', "-"),
+          _ => snake,
+        }
+      }
+    }
+  }
+}
+
+fn capitalize(segment: &str) -> String {
+  let mut chars = segment.chars();
+
+  chars
+    .next()
+    .map(|char| char.to_ascii_uppercase().to_string() + chars.as_str())
+    .unwrap_or_default()
+}
+
+fn parse_serde(
+  attrs: &[Attribute],
+  mut f: impl FnMut(ParseNestedMeta) -> Result<()>,
+) -> Result<()> {
+  for attr in attrs {
+    if attr.path().is_ident("serde") {
+      attr.parse_nested_meta(&mut f)?;
+    }
+  }
+
+  Ok(())
+}
+
+fn parse_rename(meta: &ParseNestedMeta) -> Result<Option<LitStr>> {
+  if meta.input.peek(Token![=]) {
+    return meta.value()?.parse().map(Some);
+  }
+
+  let mut output: Option<LitStr> = None;
+
+  meta.parse_nested_meta(|meta| {
+    if meta.path.is_ident("serialize") {
+      output = Some(meta.value()?.parse()?);
+    } else {
+      skip(meta)?;
+    }
+
+    Ok(())
+  })?;
+
+  Ok(output)
+}
+
+fn skip(meta: ParseNestedMeta) -> Result<()> {
+  if meta.input.peek(Token![=]) {
+    meta.value()?.parse::<syn::Expr>()?;
+  } else if meta.input.peek(syn::token::Paren) {
+    meta.parse_nested_meta(skip)?;
+  }
+
+  Ok(())
+}
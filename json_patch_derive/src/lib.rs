@@ -0,0 +1,382 @@
+//! Derive macro for [`json_patch::CanPatch`].
+//!
+//! `#[derive(JsonPatch)]` maps the reference tokens of a JSON-Pointer to the
+//! fields of a struct or enum, following the serde representation of the type.
+//! `#[serde(rename)]`, `#[serde(rename_all)]`, `#[serde(skip)]` and
+//! `#[serde(transparent)]` are honored. Enums must use the default externally
+//! tagged representation.
+//!
+//! Operations are applied in place to the targeted field, which must itself
+//! implement `CanPatch`. The type must also implement `Serialize` and
+//! `DeserializeOwned` so that whole values can be replaced, tested, copied and
+//! moved.
+mod attr;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::parse_macro_input;
+use syn::parse_quote;
+use syn::Data;
+use syn::DataEnum;
+use syn::DeriveInput;
+use syn::Error;
+use syn::Fields;
+use syn::Index;
+use syn::Member;
+use syn::Result;
+use syn::Type;
+
+use self::attr::Case;
+use self::attr::Container;
+
+#[proc_macro_derive(JsonPatch, attributes(serde))]
+pub fn derive_json_patch(input: TokenStream) -> TokenStream {
+  expand(parse_macro_input!(input as DeriveInput))
+    .unwrap_or_else(Error::into_compile_error)
+    .into()
+}
+
+/// A field addressable by a reference token.
+struct Target {
+  name: String,
+  member: Member,
+  ty: Type,
+}
+
+/// The addressable fields of a struct or enum variant.
+enum Shape {
+  /// No fields; only the value itself is addressable.
+  Unit,
+  /// A single field with the same JSON representation as the value.
+  Newtype(Box<Target>),
+  /// Fields addressed by name or index.
+  Fields(Vec<Target>),
+}
+
+impl Shape {
+  fn new(fields: &Fields, container: &Container, rename_all: Option<Case>) -> Result<Self> {
+    let mut targets: Vec<Target> = Vec::new();
+
+    for (index, field) in fields.iter().enumerate() {
+      let attrs: attr::Member = attr::Member::parse(&field.attrs)?;
+
+      if attrs.flatten {
+        return Err(Error::new_spanned(
+          field,
+          "`#[serde(flatten)]` is not supported",
+        ));
+      }
+
+      if attrs.skip {
+        continue;
+      }
+
+      let (name, member): (String, Member) = match field.ident {
+        Some(ref ident) => {
+          let name: String = match (attrs.rename, rename_all) {
+            (Some(rename), _) => rename.value(),
+            (None, Some(case)) => case.field(&ident.unraw().to_string()),
+            (None, None) => ident.unraw().to_string(),
+          };
+
+          (name, Member::Named(ident.clone()))
+        }
+        None => (
+          targets.len().to_string(),
+          Member::Unnamed(Index::from(index)),
+        ),
+      };
+
+      targets.push(Target {
+        name,
+        member,
+        ty: field.ty.clone(),
+      });
+    }
+
+    match fields {
+      Fields::Unit => Ok(Self::Unit),
+      Fields::Unnamed(_) if targets.len() == 1 && fields.len() == 1 => {
+        Ok(Self::Newtype(Box::new(targets.remove(0))))
+      }
+      Fields::Named(_) if container.transparent && targets.len() == 1 => {
+        Ok(Self::Newtype(Box::new(targets.remove(0))))
+      }
+      _ => Ok(Self::Fields(targets)),
+    }
+  }
+
+  fn types(&self) -> Vec<&Type> {
+    match self {
+      Self::Unit => Vec::new(),
+      Self::Newtype(target) => vec![&target.ty],
+      Self::Fields(targets) => targets.iter().map(|target| &target.ty).collect(),
+    }
+  }
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+  let container: Container = Container::parse(&input.attrs)?;
+
+  let (shared, unique, types): (TokenStream2, TokenStream2, Vec<Type>) = match input.data {
+    Data::Struct(ref data) => {
+      let shape: Shape = Shape::new(&data.fields, &container, container.rename_all)?;
+      let types: Vec<Type> = shape.types().into_iter().cloned().collect();
+
+      (
+        expand_struct(&shape, quote!(&)),
+        expand_struct(&shape, quote!(&mut)),
+        types,
+      )
+    }
+    Data::Enum(ref data) => {
+      if container.tagged {
+        return Err(Error::new_spanned(
+          &input.ident,
+          "only externally tagged enums are supported",
+        ));
+      }
+
+      let variants: Vec<(String, TokenStream2, Shape)> = variants(data, &container)?;
+      let types: Vec<Type> = variants
+        .iter()
+        .flat_map(|(_, _, shape)| shape.types())
+        .cloned()
+        .collect();
+
+      (
+        expand_enum(&variants, quote!(&)),
+        expand_enum(&variants, quote!(&mut)),
+        types,
+      )
+    }
+    Data::Union(_) => {
+      return Err(Error::new_spanned(&input.ident, "unions are not supported"));
+    }
+  };
+
+  let ident = &input.ident;
+  let mut generics = input.generics.clone();
+
+  if !generics.params.is_empty() {
+    let clause = generics.make_where_clause();
+
+    for ty in types {
+      clause
+        .predicates
+        .push(parse_quote!(#ty: ::json_patch::CanPatch));
+    }
+
+    clause.predicates.push(parse_quote!(
+      Self: ::json_patch::__private::Serialize + ::json_patch::__private::DeserializeOwned
+    ));
+  }
+
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  Ok(quote! {
+    impl #impl_generics ::json_patch::__private::Fields for #ident #ty_generics #where_clause {
+      #[allow(unused_variables)]
+      fn field(
+        &self,
+        __path: &str,
+      ) -> ::json_patch::Result<::json_patch::__private::Field<&dyn ::json_patch::CanPatch>> {
+        #shared
+      }
+
+      #[allow(unused_variables)]
+      fn field_mut(
+        &mut self,
+        __path: &str,
+      ) -> ::json_patch::Result<::json_patch::__private::Field<&mut dyn ::json_patch::CanPatch>> {
+        #unique
+      }
+    }
+
+    impl #impl_generics ::json_patch::CanPatch for #ident #ty_generics #where_clause {
+      fn patch_add(&mut self, op: ::json_patch::OpAdd) -> ::json_patch::Result<()> {
+        ::json_patch::__private::add(self, op)
+      }
+
+      fn patch_copy(&mut self, op: ::json_patch::OpCopy) -> ::json_patch::Result<()> {
+        ::json_patch::__private::copy(self, op)
+      }
+
+      fn patch_move(&mut self, op: ::json_patch::OpMove) -> ::json_patch::Result<()> {
+        ::json_patch::__private::move_(self, op)
+      }
+
+      fn patch_remove(&mut self, op: ::json_patch::OpRemove) -> ::json_patch::Result<()> {
+        ::json_patch::__private::remove(self, op)
+      }
+
+      fn patch_replace(&mut self, op: ::json_patch::OpReplace) -> ::json_patch::Result<()> {
+        ::json_patch::__private::replace(self, op)
+      }
+
+      fn patch_test(&mut self, op: ::json_patch::OpTest) -> ::json_patch::Result<()> {
+        ::json_patch::__private::test(self, op)
+      }
+
+      fn patch_get(&self, path: &str) -> ::json_patch::Result<::json_patch::__private::Value> {
+        ::json_patch::__private::get(self, path)
+      }
+    }
+  })
+}
+
+fn variants(data: &DataEnum, container: &Container) -> Result<Vec<(String, TokenStream2, Shape)>> {
+  let mut output: Vec<(String, TokenStream2, Shape)> = Vec::new();
+
+  for variant in data.variants.iter() {
+    let attrs: attr::Member = attr::Member::parse(&variant.attrs)?;
+
+    if attrs.skip {
+      continue;
+    }
+
+    let ident = &variant.ident;
+
+    let name: String = match (attrs.rename, container.rename_all) {
+      (Some(rename), _) => rename.value(),
+      (None, Some(case)) => case.variant(&ident.unraw().to_string()),
+      (None, None) => ident.unraw().to_string(),
+    };
+
+    let shape: Shape = Shape::new(&variant.fields, &Container::default(), attrs.rename_all)?;
+
+    let pattern: TokenStream2 = match shape {
+      Shape::Unit => continue,
+      Shape::Newtype(_) => quote!(Self::#ident(__field0)),
+      Shape::Fields(ref targets) => {
+        let members = targets.iter().map(|target| &target.member);
+        let bindings = (0..targets.len()).map(binding);
+
+        quote!(Self::#ident { #(#members: #bindings,)* .. })
+      }
+    };
+
+    output.push((name, pattern, shape));
+  }
+
+  Ok(output)
+}
+
+fn expand_struct(shape: &Shape, reference: TokenStream2) -> TokenStream2 {
+  let accessors: Vec<TokenStream2> = match shape {
+    Shape::Unit => Vec::new(),
+    Shape::Newtype(target) => vec![{
+      let member = &target.member;
+      quote!(#reference self.#member)
+    }],
+    Shape::Fields(targets) => targets
+      .iter()
+      .map(|target| {
+        let member = &target.member;
+        quote!(#reference self.#member)
+      })
+      .collect(),
+  };
+
+  expand_shape(
+    shape,
+    &accessors,
+    &reference,
+    quote!(::json_patch::__private::Field::Root),
+  )
+}
+
+fn expand_enum(
+  variants: &[(String, TokenStream2, Shape)],
+  reference: TokenStream2,
+) -> TokenStream2 {
+  let arms = variants.iter().map(|(name, pattern, shape)| {
+    let accessors: Vec<TokenStream2> = match shape {
+      Shape::Unit => Vec::new(),
+      Shape::Newtype(_) => vec![binding(0)],
+      Shape::Fields(targets) => (0..targets.len()).map(binding).collect(),
+    };
+
+    let inner: TokenStream2 = expand_shape(
+      shape,
+      &accessors,
+      &reference,
+      quote!(::json_patch::__private::Field::Variant(#name)),
+    );
+
+    quote! {
+      #pattern if __token == #name => {
+        let __path: &str = &__path;
+        #inner
+      }
+    }
+  });
+
+  quote! {
+    let (__token, __path) = match ::json_patch::__private::split(__path)? {
+      ::core::option::Option::Some(__split) => __split,
+      ::core::option::Option::None => {
+        return ::core::result::Result::Ok(::json_patch::__private::Field::Root);
+      }
+    };
+
+    match self {
+      #(#arms)*
+      _ => ::core::result::Result::Err(::json_patch::Error::InvalidPointer),
+    }
+  }
+}
+
+fn expand_shape(
+  shape: &Shape,
+  accessors: &[TokenStream2],
+  reference: &TokenStream2,
+  root: TokenStream2,
+) -> TokenStream2 {
+  match shape {
+    Shape::Unit => quote! {
+      if __path.is_empty() {
+        ::core::result::Result::Ok(#root)
+      } else {
+        ::core::result::Result::Err(::json_patch::Error::InvalidPointer)
+      }
+    },
+    Shape::Newtype(_) => {
+      let accessor = &accessors[0];
+
+      quote! {
+        ::core::result::Result::Ok(::json_patch::__private::Field::Field(
+          #accessor as #reference dyn ::json_patch::CanPatch,
+          __path.into(),
+        ))
+      }
+    }
+    Shape::Fields(targets) => {
+      let names = targets.iter().map(|target| &target.name);
+
+      quote! {
+        let (__token, __path) = match ::json_patch::__private::split(__path)? {
+          ::core::option::Option::Some(__split) => __split,
+          ::core::option::Option::None => return ::core::result::Result::Ok(#root),
+        };
+
+        match &*__token {
+          #(
+            #names => ::core::result::Result::Ok(::json_patch::__private::Field::Field(
+              #accessors as #reference dyn ::json_patch::CanPatch,
+              __path,
+            )),
+          )*
+          _ => ::core::result::Result::Err(::json_patch::Error::InvalidPointer),
+        }
+      }
+    }
+  }
+}
+
+fn binding(index: usize) -> TokenStream2 {
+  let ident = quote::format_ident!("__field{}", index);
+  quote!(#ident)
+}
@@ -0,0 +1,154 @@
+use json_patch::CanPatch;
+use json_patch::Error;
+use json_patch::JsonPatch;
+use json_patch::Patch;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::from_value;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonPatch)]
+#[serde(rename_all = "camelCase")]
+struct Dashboard {
+  title: String,
+  widget_count: u32,
+  #[serde(rename = "owner")]
+  created_by: Option<String>,
+  widgets: Vec<Widget>,
+  labels: BTreeMap<String, String>,
+  #[serde(skip)]
+  cache: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonPatch)]
+#[serde(rename_all = "snake_case")]
+enum Widget {
+  Chart { series: Vec<f64>, title: Title },
+  Text(String),
+  Empty,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonPatch)]
+struct Title(String);
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonPatch)]
+struct Wrapper<T> {
+  inner: T,
+}
+
+fn dashboard() -> Dashboard {
+  Dashboard {
+    title: "main".into(),
+    widget_count: 2,
+    created_by: None,
+    widgets: vec![
+      Widget::Chart {
+        series: vec![1.0, 2.0],
+        title: Title("chart".into()),
+      },
+      Widget::Text("hello".into()),
+    ],
+    labels: BTreeMap::new(),
+    cache: 7,
+  }
+}
+
+fn apply(value: &mut impl CanPatch, patch: serde_json::Value) -> json_patch::Result<()> {
+  from_value::<Patch>(patch).unwrap().apply_in_place(value)
+}
+
+#[test]
+fn test_fields() {
+  let mut value: Dashboard = dashboard();
+
+  apply(
+    &mut value,
+    json!([
+      {"op": "test", "path": "/widgetCount", "value": 2},
+      {"op": "replace", "path": "/title", "value": "renamed"},
+      {"op": "add", "path": "/owner", "value": "admin"},
+      {"op": "add", "path": "/labels/env", "value": "prod"},
+      {"op": "add", "path": "/widgets/0/chart/series/-", "value": 3.0},
+      {"op": "replace", "path": "/widgets/1/text", "value": "world"},
+      {"op": "add", "path": "/widgets/-", "value": "empty"},
+      {"op": "copy", "from": "/title", "path": "/widgets/0/chart/title"},
+      {"op": "move", "from": "/labels/env", "path": "/labels/stage"},
+      {"op": "remove", "path": "/owner"},
+    ]),
+  )
+  .unwrap();
+
+  assert_eq!(value.title, "renamed");
+  assert_eq!(value.created_by, None);
+  assert_eq!(value.labels.get("stage").map(String::as_str), Some("prod"));
+  assert_eq!(value.cache, 7);
+  assert_eq!(
+    value.widgets,
+    [
+      Widget::Chart {
+        series: vec![1.0, 2.0, 3.0],
+        title: Title("renamed".into()),
+      },
+      Widget::Text("world".into()),
+      Widget::Empty,
+    ]
+  );
+}
+
+#[test]
+fn test_errors() {
+  let mut value: Dashboard = dashboard();
+
+  let error: Error = apply(
+    &mut value,
+    json!([{"op": "add", "path": "/cache", "value": 1}]),
+  )
+  .unwrap_err();
+  assert!(matches!(error, Error::InvalidPointer));
+
+  let error: Error = apply(
+    &mut value,
+    json!([{"op": "add", "path": "/widgets/1/chart", "value": {}}]),
+  )
+  .unwrap_err();
+  assert!(matches!(error, Error::InvalidPointer));
+
+  let error: Error = apply(
+    &mut value,
+    json!([{"op": "test", "path": "/title", "value": "other"}]),
+  )
+  .unwrap_err();
+  assert!(matches!(error, Error::InvalidTest));
+
+  let error: Error = apply(
+    &mut value,
+    json!([{"op": "replace", "path": "/widgetCount", "value": "two"}]),
+  )
+  .unwrap_err();
+  assert!(matches!(error, Error::InvalidValue { .. }));
+
+  let error: Error = apply(&mut value, json!([{"op": "remove", "path": "/title"}])).unwrap_err();
+  assert!(matches!(error, Error::InvalidPointer));
+
+  assert_eq!(value, dashboard());
+}
+
+#[test]
+fn test_generic() {
+  let mut value: Wrapper<Vec<u8>> = Wrapper { inner: vec![1] };
+
+  apply(
+    &mut value,
+    json!([{"op": "add", "path": "/inner/0", "value": 0}]),
+  )
+  .unwrap();
+  apply(
+    &mut value,
+    json!([{"op": "replace", "path": "", "value": {"inner": [0, 1, 2]}}]),
+  )
+  .unwrap();
+
+  assert_eq!(value.inner, [0, 1, 2]);
+  assert_eq!(value.patch_get("/inner/2").unwrap(), json!(2));
+}
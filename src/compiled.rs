@@ -0,0 +1,248 @@
+#[cfg(feature = "rayon")]
+use rayon::iter::IntoParallelRefMutIterator;
+#[cfg(feature = "rayon")]
+use rayon::iter::ParallelIterator;
+use serde_json::Value;
+
+use crate::document::Document;
+use crate::document::NodeKind;
+use crate::error::Error;
+use crate::error::Result;
+use crate::operation::parse_idx;
+use crate::operation::OpTest;
+use crate::operation::Operation;
+use crate::patch::prepare;
+use crate::patch::Patch;
+use crate::pointer::is_prefix;
+use crate::pointer::tokens;
+
+/// A JSON Patch with every `path` and `from` pointer parsed and validated.
+///
+/// Created by [`Patch::compile`] for patches applied to many documents.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompiledPatch(Vec<Step>);
+
+#[derive(Clone, Debug, PartialEq)]
+enum Step {
+  Add(Vec<Token>, Value),
+  Remove(Vec<Token>),
+  Replace(Vec<Token>, Value),
+  Move(Vec<Token>, Vec<Token>),
+  Copy(Vec<Token>, Vec<Token>),
+  Test(Vec<Token>, OpTest),
+  Edit(Vec<Token>, Operation),
+}
+
+/// An unescaped reference token along with its value as an array index.
+#[derive(Clone, Debug, PartialEq)]
+struct Token {
+  key: String,
+  index: Option<usize>,
+}
+
+impl CompiledPatch {
+  /// Compiles the operations of `patch`.
+  ///
+  /// Fails with [`Error::InvalidPointer`] if any pointer does not start with
+  /// `/` or an operation can never succeed, e.g. removing the whole document.
+  pub fn new(patch: &Patch) -> Result<Self> {
+    patch.iter().map(Step::new).collect::<Result<_>>().map(Self)
+  }
+
+  pub fn len(&self) -> usize {
+    self.0.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  pub fn apply_ref<D: Document>(&self, output: &D) -> Result<D> {
+    let mut value: D = output.clone();
+
+    self.apply_mut(&mut value)?;
+
+    Ok(value)
+  }
+
+  pub fn apply_mut<D: Document>(&self, output: &mut D) -> Result<()> {
+    if self.is_empty() {
+      return Ok(());
+    }
+
+    prepare(output)?;
+
+    for step in self.0.iter() {
+      step.apply(output)?;
+    }
+
+    Ok(())
+  }
+
+  /// Applies the patch to each document of `docs`.
+  ///
+  /// Each document is left unchanged if the patch fails to apply to it.
+  pub fn apply_many<D: Document>(&self, docs: &mut [D]) -> Vec<Result<()>> {
+    docs.iter_mut().map(|doc| self.apply_atomic(doc)).collect()
+  }
+
+  /// Applies the patch to each document of `docs` concurrently.
+  ///
+  /// Each document is left unchanged if the patch fails to apply to it.
+  #[cfg(feature = "rayon")]
+  pub fn apply_many_par<D: Document + Send>(&self, docs: &mut [D]) -> Vec<Result<()>> {
+    docs
+      .par_iter_mut()
+      .map(|doc| self.apply_atomic(doc))
+      .collect()
+  }
+
+  fn apply_atomic<D: Document>(&self, output: &mut D) -> Result<()> {
+    *output = self.apply_ref(output)?;
+    Ok(())
+  }
+}
+
+impl Step {
+  fn new(operation: &Operation) -> Result<Self> {
+    match operation {
+      Operation::Add(op) => Ok(Self::Add(compile(&op.path)?, op.value.clone())),
+      Operation::Remove(op) => Ok(Self::Remove(compile_child(&op.path)?)),
+      Operation::Replace(op) => Ok(Self::Replace(compile(&op.path)?, op.value.clone())),
+      Operation::Move(op) => {
+        // The "from" location MUST NOT be a proper prefix of the "path"
+        // location; i.e., a location cannot be moved into one of its children.
+        if op.from != op.path && is_prefix(&op.from, &op.path) {
+          return Err(Error::InvalidPointer);
+        }
+
+        Ok(Self::Move(compile_child(&op.from)?, compile(&op.path)?))
+      }
+      Operation::Copy(op) => Ok(Self::Copy(compile(&op.from)?, compile(&op.path)?)),
+      Operation::Test(op) => Ok(Self::Test(compile(&op.path)?, op.clone())),
+      operation => Ok(Self::Edit(compile(operation.path())?, operation.clone())),
+    }
+  }
+
+  fn apply<D: Document>(&self, output: &mut D) -> Result<()> {
+    match self {
+      Self::Add(path, value) => add(output, path, D::from_value(value.clone())?),
+      Self::Remove(path) => remove(output, path).map(|_| ()),
+      Self::Replace(path, value) => {
+        let value: D = D::from_value(value.clone())?;
+        *pointer_mut(output, path)? = value;
+        Ok(())
+      }
+      Self::Move(from, path) => {
+        let value: D = remove(output, from)?;
+        add(output, path, value)
+      }
+      Self::Copy(from, path) => {
+        let value: D = pointer(output, from)?.clone();
+        add(output, path, value)
+      }
+      Self::Test(path, op) => op.check(pointer(output, path).ok()),
+      Self::Edit(path, operation) => operation.edit(pointer_mut(output, path)?),
+    }
+  }
+}
+
+fn compile(pointer: &str) -> Result<Vec<Token>> {
+  // Like `apply_mut`, only the leading slash is validated; malformed escapes
+  // such as `~2` are kept as they are.
+  if !pointer.is_empty() && !pointer.starts_with('/') {
+    return Err(Error::InvalidPointer);
+  }
+
+  Ok(
+    tokens(pointer)
+      .map(|key| Token {
+        index: parse_idx(&key, usize::MAX).ok(),
+        key: key.into_owned(),
+      })
+      .collect(),
+  )
+}
+
+/// Compiles a pointer that must reference a location within the document.
+fn compile_child(pointer: &str) -> Result<Vec<Token>> {
+  compile(pointer).and_then(|tokens| {
+    if tokens.is_empty() {
+      Err(Error::InvalidPointer)
+    } else {
+      Ok(tokens)
+    }
+  })
+}
+
+fn index(token: &Token, limit: usize) -> Result<usize> {
+  match token.index {
+    Some(index) if index < limit => Ok(index),
+    Some(_) | None => Err(Error::InvalidPointer),
+  }
+}
+
+fn pointer<'a, D: Document>(value: &'a D, tokens: &[Token]) -> Result<&'a D> {
+  tokens
+    .iter()
+    .try_fold(value, |value, token| match value.kind() {
+      NodeKind::Object => value.object_get(&token.key).ok_or(Error::InvalidPointer),
+      NodeKind::Array => value
+        .array_get(index(token, value.array_len())?)
+        .ok_or(Error::InvalidPointer),
+      NodeKind::Other => Err(Error::InvalidPointer),
+    })
+}
+
+fn pointer_mut<'a, D: Document>(value: &'a mut D, tokens: &[Token]) -> Result<&'a mut D> {
+  tokens
+    .iter()
+    .try_fold(value, |value, token| match value.kind() {
+      NodeKind::Object => value
+        .object_get_mut(&token.key)
+        .ok_or(Error::InvalidPointer),
+      NodeKind::Array => {
+        let index: usize = index(token, value.array_len())?;
+        value.array_get_mut(index).ok_or(Error::InvalidPointer)
+      }
+      NodeKind::Other => Err(Error::InvalidPointer),
+    })
+}
+
+fn add<D: Document>(output: &mut D, path: &[Token], value: D) -> Result<()> {
+  let (last, path): (&Token, &[Token]) = match path.split_last() {
+    Some(split) => split,
+    None => {
+      *output = value;
+      return Ok(());
+    }
+  };
+
+  let inner: &mut D = pointer_mut(output, path)?;
+
+  match inner.kind() {
+    NodeKind::Object => {
+      let _: Option<D> = inner.object_insert(last.key.clone(), value);
+    }
+    NodeKind::Array if last.key == "-" => {
+      inner.array_insert(inner.array_len(), value);
+    }
+    NodeKind::Array => {
+      inner.array_insert(index(last, inner.array_len() + 1)?, value);
+    }
+    NodeKind::Other => return Err(Error::InvalidPointer),
+  }
+
+  Ok(())
+}
+
+fn remove<D: Document>(output: &mut D, path: &[Token]) -> Result<D> {
+  let (last, path): (&Token, &[Token]) = path.split_last().ok_or(Error::InvalidPointer)?;
+  let inner: &mut D = pointer_mut(output, path)?;
+
+  match inner.kind() {
+    NodeKind::Object => inner.object_remove(&last.key).ok_or(Error::InvalidPointer),
+    NodeKind::Array => Ok(inner.array_remove(index(last, inner.array_len())?)),
+    NodeKind::Other => Err(Error::InvalidPointer),
+  }
+}
@@ -0,0 +1,175 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use std::borrow::Cow;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::operation::OpAdd;
+use crate::operation::OpCopy;
+use crate::operation::OpMove;
+use crate::operation::OpRemove;
+use crate::operation::OpReplace;
+use crate::operation::OpTest;
+use crate::pointer::is_prefix;
+use crate::pointer::unescape;
+use crate::pointer::Pointer;
+use crate::traits::CanPatch;
+
+/// The resolved target of a JSON-Pointer within a value.
+pub enum Field<T> {
+  /// The pointer references the value itself.
+  Root,
+  /// The pointer references the content of the active enum variant.
+  Variant(&'static str),
+  /// The pointer references a location within a field of the value.
+  Field(T, String),
+}
+
+/// Resolves JSON-Pointers to the fields of a value.
+pub trait Fields {
+  fn field(&self, path: &str) -> Result<Field<&dyn CanPatch>>;
+  fn field_mut(&mut self, path: &str) -> Result<Field<&mut dyn CanPatch>>;
+}
+
+/// Splits the first unescaped reference token from `path`.
+pub fn split(path: &str) -> Result<Option<(Cow<'_, str>, String)>> {
+  if path.is_empty() {
+    return Ok(None);
+  }
+
+  let path: &str = path.strip_prefix('/').ok_or(Error::InvalidPointer)?;
+
+  match path.find('/') {
+    Some(index) => Ok(Some((unescape(&path[..index]), path[index..].into()))),
+    None => Ok(Some((unescape(path), String::new()))),
+  }
+}
+
+pub fn add<T>(this: &mut T, op: OpAdd) -> Result<()>
+where
+  T: Fields + DeserializeOwned,
+{
+  match this.field_mut(&op.path)? {
+    Field::Root => set(this, op.value),
+    Field::Variant(name) => set(this, variant(name, op.value)),
+    Field::Field(field, path) => field.patch_add(OpAdd {
+      path,
+      value: op.value,
+    }),
+  }
+}
+
+pub fn remove<T>(this: &mut T, op: OpRemove) -> Result<()>
+where
+  T: Fields,
+{
+  match this.field_mut(&op.path)? {
+    Field::Root | Field::Variant(_) => Err(Error::InvalidPointer),
+    Field::Field(field, path) => field.patch_remove(OpRemove { path }),
+  }
+}
+
+pub fn replace<T>(this: &mut T, op: OpReplace) -> Result<()>
+where
+  T: Fields + DeserializeOwned,
+{
+  match this.field_mut(&op.path)? {
+    Field::Root => set(this, op.value),
+    Field::Variant(name) => set(this, variant(name, op.value)),
+    Field::Field(field, path) => field.patch_replace(OpReplace {
+      path,
+      value: op.value,
+    }),
+  }
+}
+
+pub fn test<T>(this: &mut T, op: OpTest) -> Result<()>
+where
+  T: Fields + Serialize,
+{
+  match this.field_mut(&op.path) {
+    Ok(Field::Root) => op.check(Some(&to_json(this)?)),
+    Ok(Field::Variant(name)) => op.check(Some(&content(to_json(this)?, name)?)),
+    Ok(Field::Field(field, path)) => field.patch_test(OpTest { path, ..op }),
+    Err(Error::InvalidPointer) => op.check(None::<&Value>),
+    Err(error) => Err(error),
+  }
+}
+
+pub fn get<T>(this: &T, path: &str) -> Result<Value>
+where
+  T: Fields + Serialize,
+{
+  match this.field(path)? {
+    Field::Root => to_json(this),
+    Field::Variant(name) => content(to_json(this)?, name),
+    Field::Field(field, path) => field.patch_get(&path),
+  }
+}
+
+pub fn move_<T>(this: &mut T, op: OpMove) -> Result<()>
+where
+  T: CanPatch + ?Sized,
+{
+  if op.from != op.path && is_prefix(&op.from, &op.path) {
+    return Err(Error::InvalidPointer);
+  }
+
+  let value: Value = this.patch_get(&op.from)?;
+
+  this.patch_remove(OpRemove { path: op.from })?;
+  this.patch_add(OpAdd {
+    path: op.path,
+    value,
+  })
+}
+
+pub fn copy<T>(this: &mut T, op: OpCopy) -> Result<()>
+where
+  T: CanPatch + ?Sized,
+{
+  let value: Value = this.patch_get(&op.from)?;
+
+  this.patch_add(OpAdd {
+    path: op.path,
+    value,
+  })
+}
+
+pub fn set<T>(this: &mut T, value: Value) -> Result<()>
+where
+  T: DeserializeOwned,
+{
+  *this = serde_json::from_value(value).map_err(invalid_value)?;
+
+  Ok(())
+}
+
+pub fn to_json<T>(this: &T) -> Result<Value>
+where
+  T: Serialize + ?Sized,
+{
+  serde_json::to_value(this).map_err(invalid_value)
+}
+
+fn variant(name: &str, value: Value) -> Value {
+  let mut output: Map<String, Value> = Map::new();
+  output.insert(name.into(), value);
+  Value::Object(output)
+}
+
+fn content(value: Value, name: &str) -> Result<Value> {
+  match value {
+    Value::Object(mut inner) => inner.remove(name).ok_or(Error::InvalidPointer),
+    _ => Err(Error::InvalidPointer),
+  }
+}
+
+fn invalid_value(error: serde_json::Error) -> Error {
+  Error::InvalidValue {
+    path: Pointer::root(),
+    error,
+  }
+}
@@ -0,0 +1,132 @@
+use serde_json::Value;
+
+#[cfg(doc)]
+use crate::error::Error;
+use crate::error::Result;
+
+/// The type of a node within a [`Document`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+  Object,
+  Array,
+  Other,
+}
+
+/// A JSON-like document tree that JSON Patch operations can be applied to.
+///
+/// Object methods are only called on nodes of kind [`NodeKind::Object`] and
+/// array methods on nodes of kind [`NodeKind::Array`]; array indices are
+/// validated before use.
+pub trait Document: Clone {
+  /// Creates a node from the JSON value of an `add` or `replace` operation.
+  ///
+  /// Fails with [`Error::UnsupportedValue`] if the value cannot be represented
+  /// by the document type.
+  fn from_value(value: Value) -> Result<Self>;
+
+  /// Returns `true` if the node is equal to the JSON value of a `test`
+  /// operation.
+  fn eq_value(&self, value: &Value) -> bool;
+
+  /// Returns the type of the node.
+  fn kind(&self) -> NodeKind;
+
+  /// Returns the JSON value of a node of kind [`NodeKind::Other`], used by
+  /// the `type`, `lt` and `gt` conditions of extended `test` operations.
+  ///
+  /// Returns `None` by default, failing such tests with
+  /// [`Error::UnsupportedValue`].
+  fn to_scalar(&self) -> Option<Value> {
+    None
+  }
+
+  /// Returns the JSON value of the node, used by custom operations.
+  ///
+  /// Returns `None` by default, failing such operations with
+  /// [`Error::UnsupportedValue`].
+  fn to_value(&self) -> Option<Value> {
+    None
+  }
+
+  fn object_get(&self, key: &str) -> Option<&Self>;
+  fn object_get_mut(&mut self, key: &str) -> Option<&mut Self>;
+  fn object_insert(&mut self, key: String, value: Self) -> Option<Self>;
+  fn object_remove(&mut self, key: &str) -> Option<Self>;
+
+  fn array_len(&self) -> usize;
+  fn array_get(&self, index: usize) -> Option<&Self>;
+  fn array_get_mut(&mut self, index: usize) -> Option<&mut Self>;
+  fn array_insert(&mut self, index: usize, value: Self);
+  fn array_remove(&mut self, index: usize) -> Self;
+}
+
+impl Document for Value {
+  fn from_value(value: Value) -> Result<Self> {
+    Ok(value)
+  }
+
+  fn eq_value(&self, value: &Value) -> bool {
+    self == value
+  }
+
+  fn kind(&self) -> NodeKind {
+    match self {
+      Self::Object(_) => NodeKind::Object,
+      Self::Array(_) => NodeKind::Array,
+      _ => NodeKind::Other,
+    }
+  }
+
+  fn to_scalar(&self) -> Option<Value> {
+    match self {
+      Self::Object(_) | Self::Array(_) => None,
+      _ => Some(self.clone()),
+    }
+  }
+
+  fn to_value(&self) -> Option<Value> {
+    Some(self.clone())
+  }
+
+  fn object_get(&self, key: &str) -> Option<&Self> {
+    self.as_object().and_then(|inner| inner.get(key))
+  }
+
+  fn object_get_mut(&mut self, key: &str) -> Option<&mut Self> {
+    self.as_object_mut().and_then(|inner| inner.get_mut(key))
+  }
+
+  fn object_insert(&mut self, key: String, value: Self) -> Option<Self> {
+    self
+      .as_object_mut()
+      .and_then(|inner| inner.insert(key, value))
+  }
+
+  fn object_remove(&mut self, key: &str) -> Option<Self> {
+    self.as_object_mut().and_then(|inner| inner.remove(key))
+  }
+
+  fn array_len(&self) -> usize {
+    self.as_array().map_or(0, Vec::len)
+  }
+
+  fn array_get(&self, index: usize) -> Option<&Self> {
+    self.as_array().and_then(|inner| inner.get(index))
+  }
+
+  fn array_get_mut(&mut self, index: usize) -> Option<&mut Self> {
+    self.as_array_mut().and_then(|inner| inner.get_mut(index))
+  }
+
+  fn array_insert(&mut self, index: usize, value: Self) {
+    if let Some(inner) = self.as_array_mut() {
+      inner.insert(index, value);
+    }
+  }
+
+  fn array_remove(&mut self, index: usize) -> Self {
+    self
+      .as_array_mut()
+      .map_or(Value::Null, |inner| inner.remove(index))
+  }
+}
@@ -0,0 +1,147 @@
+use serde_json::Number;
+use serde_json::Value;
+use toml::Value as Toml;
+
+use crate::document::Document;
+use crate::document::NodeKind;
+use crate::error::Error;
+use crate::error::Result;
+
+/// TOML documents.
+///
+/// TOML has no `null`, so JSON `null` values are rejected with
+/// [`Error::UnsupportedValue`]. Datetimes are compared with JSON strings by
+/// their RFC 3339 representation.
+impl Document for Toml {
+  fn from_value(value: Value) -> Result<Self> {
+    match value {
+      Value::Null => Err(Error::UnsupportedValue),
+      Value::Bool(inner) => Ok(Self::Boolean(inner)),
+      Value::Number(inner) => number(&inner),
+      Value::String(inner) => Ok(Self::String(inner)),
+      Value::Array(inner) => inner
+        .into_iter()
+        .map(Self::from_value)
+        .collect::<Result<_>>()
+        .map(Self::Array),
+      Value::Object(inner) => inner
+        .into_iter()
+        .map(|(key, value)| Self::from_value(value).map(|value| (key, value)))
+        .collect::<Result<_>>()
+        .map(Self::Table),
+    }
+  }
+
+  fn eq_value(&self, value: &Value) -> bool {
+    match (self, value) {
+      (Self::Boolean(lhs), Value::Bool(rhs)) => lhs == rhs,
+      (Self::Integer(lhs), Value::Number(rhs)) => match rhs.as_i64() {
+        Some(rhs) => *lhs == rhs,
+        None => rhs.as_f64() == Some(*lhs as f64),
+      },
+      (Self::Float(lhs), Value::Number(rhs)) => rhs.as_f64() == Some(*lhs),
+      (Self::String(lhs), Value::String(rhs)) => lhs == rhs,
+      (Self::Datetime(lhs), Value::String(rhs)) => lhs.to_string() == *rhs,
+      (Self::Array(lhs), Value::Array(rhs)) => {
+        lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(lhs, rhs)| lhs.eq_value(rhs))
+      }
+      (Self::Table(lhs), Value::Object(rhs)) => {
+        lhs.len() == rhs.len()
+          && lhs
+            .iter()
+            .all(|(key, lhs)| matches!(rhs.get(key), Some(rhs) if lhs.eq_value(rhs)))
+      }
+      (_, _) => false,
+    }
+  }
+
+  fn kind(&self) -> NodeKind {
+    match self {
+      Self::Table(_) => NodeKind::Object,
+      Self::Array(_) => NodeKind::Array,
+      _ => NodeKind::Other,
+    }
+  }
+
+  fn to_scalar(&self) -> Option<Value> {
+    match self {
+      Self::Boolean(inner) => Some(Value::Bool(*inner)),
+      Self::Integer(inner) => Some(Value::from(*inner)),
+      Self::Float(inner) => Number::from_f64(*inner).map(Value::Number),
+      Self::String(inner) => Some(Value::String(inner.clone())),
+      Self::Datetime(inner) => Some(Value::String(inner.to_string())),
+      Self::Array(_) | Self::Table(_) => None,
+    }
+  }
+
+  fn to_value(&self) -> Option<Value> {
+    match self {
+      Self::Array(inner) => inner
+        .iter()
+        .map(Self::to_value)
+        .collect::<Option<_>>()
+        .map(Value::Array),
+      Self::Table(inner) => inner
+        .iter()
+        .map(|(key, value)| value.to_value().map(|value| (key.clone(), value)))
+        .collect::<Option<_>>()
+        .map(Value::Object),
+      _ => self.to_scalar(),
+    }
+  }
+
+  fn object_get(&self, key: &str) -> Option<&Self> {
+    self.as_table().and_then(|inner| inner.get(key))
+  }
+
+  fn object_get_mut(&mut self, key: &str) -> Option<&mut Self> {
+    self.as_table_mut().and_then(|inner| inner.get_mut(key))
+  }
+
+  fn object_insert(&mut self, key: String, value: Self) -> Option<Self> {
+    self
+      .as_table_mut()
+      .and_then(|inner| inner.insert(key, value))
+  }
+
+  fn object_remove(&mut self, key: &str) -> Option<Self> {
+    self.as_table_mut().and_then(|inner| inner.remove(key))
+  }
+
+  fn array_len(&self) -> usize {
+    self.as_array().map_or(0, Vec::len)
+  }
+
+  fn array_get(&self, index: usize) -> Option<&Self> {
+    self.as_array().and_then(|inner| inner.get(index))
+  }
+
+  fn array_get_mut(&mut self, index: usize) -> Option<&mut Self> {
+    self.as_array_mut().and_then(|inner| inner.get_mut(index))
+  }
+
+  fn array_insert(&mut self, index: usize, value: Self) {
+    if let Some(inner) = self.as_array_mut() {
+      inner.insert(index, value);
+    }
+  }
+
+  fn array_remove(&mut self, index: usize) -> Self {
+    self
+      .as_array_mut()
+      .map_or(Self::Array(Vec::new()), |inner| inner.remove(index))
+  }
+}
+
+fn number(number: &Number) -> Result<Toml> {
+  if let Some(inner) = number.as_i64() {
+    Ok(Toml::Integer(inner))
+  } else if number.is_u64() {
+    Err(Error::UnsupportedValue)
+  } else {
+    number
+      .as_f64()
+      .map(Toml::Float)
+      .ok_or(Error::UnsupportedValue)
+  }
+}
@@ -0,0 +1,162 @@
+use serde_json::Number;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::document::Document;
+use crate::document::NodeKind;
+use crate::error::Result;
+
+/// A JSON value whose objects are stored in a [`BTreeMap`], keeping their
+/// members sorted by key.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum TreeValue {
+  Null,
+  Bool(bool),
+  Number(Number),
+  String(String),
+  Array(Vec<TreeValue>),
+  Object(BTreeMap<String, TreeValue>),
+}
+
+impl From<Value> for TreeValue {
+  fn from(other: Value) -> Self {
+    match other {
+      Value::Null => Self::Null,
+      Value::Bool(inner) => Self::Bool(inner),
+      Value::Number(inner) => Self::Number(inner),
+      Value::String(inner) => Self::String(inner),
+      Value::Array(inner) => Self::Array(inner.into_iter().map(Self::from).collect()),
+      Value::Object(inner) => Self::Object(
+        inner
+          .into_iter()
+          .map(|(key, value)| (key, Self::from(value)))
+          .collect(),
+      ),
+    }
+  }
+}
+
+impl From<TreeValue> for Value {
+  fn from(other: TreeValue) -> Self {
+    match other {
+      TreeValue::Null => Self::Null,
+      TreeValue::Bool(inner) => Self::Bool(inner),
+      TreeValue::Number(inner) => Self::Number(inner),
+      TreeValue::String(inner) => Self::String(inner),
+      TreeValue::Array(inner) => Self::Array(inner.into_iter().map(Self::from).collect()),
+      TreeValue::Object(inner) => Self::Object(
+        inner
+          .into_iter()
+          .map(|(key, value)| (key, Self::from(value)))
+          .collect(),
+      ),
+    }
+  }
+}
+
+impl Document for TreeValue {
+  fn from_value(value: Value) -> Result<Self> {
+    Ok(Self::from(value))
+  }
+
+  fn eq_value(&self, value: &Value) -> bool {
+    match (self, value) {
+      (Self::Null, Value::Null) => true,
+      (Self::Bool(lhs), Value::Bool(rhs)) => lhs == rhs,
+      (Self::Number(lhs), Value::Number(rhs)) => lhs == rhs,
+      (Self::String(lhs), Value::String(rhs)) => lhs == rhs,
+      (Self::Array(lhs), Value::Array(rhs)) => {
+        lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(lhs, rhs)| lhs.eq_value(rhs))
+      }
+      (Self::Object(lhs), Value::Object(rhs)) => {
+        lhs.len() == rhs.len()
+          && lhs
+            .iter()
+            .all(|(key, lhs)| rhs.get(key).is_some_and(|rhs| lhs.eq_value(rhs)))
+      }
+      _ => false,
+    }
+  }
+
+  fn kind(&self) -> NodeKind {
+    match self {
+      Self::Object(_) => NodeKind::Object,
+      Self::Array(_) => NodeKind::Array,
+      _ => NodeKind::Other,
+    }
+  }
+
+  fn to_scalar(&self) -> Option<Value> {
+    match self {
+      Self::Object(_) | Self::Array(_) => None,
+      _ => Some(Value::from(self.clone())),
+    }
+  }
+
+  fn to_value(&self) -> Option<Value> {
+    Some(Value::from(self.clone()))
+  }
+
+  fn object_get(&self, key: &str) -> Option<&Self> {
+    match self {
+      Self::Object(inner) => inner.get(key),
+      _ => None,
+    }
+  }
+
+  fn object_get_mut(&mut self, key: &str) -> Option<&mut Self> {
+    match self {
+      Self::Object(inner) => inner.get_mut(key),
+      _ => None,
+    }
+  }
+
+  fn object_insert(&mut self, key: String, value: Self) -> Option<Self> {
+    match self {
+      Self::Object(inner) => inner.insert(key, value),
+      _ => None,
+    }
+  }
+
+  fn object_remove(&mut self, key: &str) -> Option<Self> {
+    match self {
+      Self::Object(inner) => inner.remove(key),
+      _ => None,
+    }
+  }
+
+  fn array_len(&self) -> usize {
+    match self {
+      Self::Array(inner) => inner.len(),
+      _ => 0,
+    }
+  }
+
+  fn array_get(&self, index: usize) -> Option<&Self> {
+    match self {
+      Self::Array(inner) => inner.get(index),
+      _ => None,
+    }
+  }
+
+  fn array_get_mut(&mut self, index: usize) -> Option<&mut Self> {
+    match self {
+      Self::Array(inner) => inner.get_mut(index),
+      _ => None,
+    }
+  }
+
+  fn array_insert(&mut self, index: usize, value: Self) {
+    if let Self::Array(inner) = self {
+      inner.insert(index, value);
+    }
+  }
+
+  fn array_remove(&mut self, index: usize) -> Self {
+    match self {
+      Self::Array(inner) => inner.remove(index),
+      _ => Self::Null,
+    }
+  }
+}
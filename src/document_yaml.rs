@@ -0,0 +1,150 @@
+use serde_json::Value;
+use serde_yaml::Mapping;
+use serde_yaml::Number;
+use serde_yaml::Value as Yaml;
+
+use crate::document::Document;
+use crate::document::NodeKind;
+use crate::error::Result;
+
+/// YAML documents.
+///
+/// Only mappings with string keys are addressable by JSON-Pointers. Tagged
+/// values are compared by their inner value.
+impl Document for Yaml {
+  fn from_value(value: Value) -> Result<Self> {
+    match value {
+      Value::Null => Ok(Self::Null),
+      Value::Bool(inner) => Ok(Self::Bool(inner)),
+      Value::Number(inner) => Ok(Self::Number(number(&inner))),
+      Value::String(inner) => Ok(Self::String(inner)),
+      Value::Array(inner) => inner
+        .into_iter()
+        .map(Self::from_value)
+        .collect::<Result<_>>()
+        .map(Self::Sequence),
+      Value::Object(inner) => inner
+        .into_iter()
+        .map(|(key, value)| Self::from_value(value).map(|value| (Self::String(key), value)))
+        .collect::<Result<Mapping>>()
+        .map(Self::Mapping),
+    }
+  }
+
+  fn eq_value(&self, value: &Value) -> bool {
+    match (self, value) {
+      (Self::Null, Value::Null) => true,
+      (Self::Bool(lhs), Value::Bool(rhs)) => lhs == rhs,
+      (Self::Number(lhs), Value::Number(rhs)) => match (lhs.as_i64(), rhs.as_i64()) {
+        (Some(lhs), Some(rhs)) => lhs == rhs,
+        (_, _) => match (lhs.as_u64(), rhs.as_u64()) {
+          (Some(lhs), Some(rhs)) => lhs == rhs,
+          (_, _) => lhs.as_f64().is_some() && lhs.as_f64() == rhs.as_f64(),
+        },
+      },
+      (Self::String(lhs), Value::String(rhs)) => lhs == rhs,
+      (Self::Sequence(lhs), Value::Array(rhs)) => {
+        lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(lhs, rhs)| lhs.eq_value(rhs))
+      }
+      (Self::Mapping(lhs), Value::Object(rhs)) => {
+        lhs.len() == rhs.len()
+          && lhs.iter().all(|(key, lhs)| match key.as_str() {
+            Some(key) => matches!(rhs.get(key), Some(rhs) if lhs.eq_value(rhs)),
+            None => false,
+          })
+      }
+      (Self::Tagged(lhs), _) => lhs.value.eq_value(value),
+      (_, _) => false,
+    }
+  }
+
+  fn kind(&self) -> NodeKind {
+    match self {
+      Self::Mapping(_) => NodeKind::Object,
+      Self::Sequence(_) => NodeKind::Array,
+      _ => NodeKind::Other,
+    }
+  }
+
+  fn to_scalar(&self) -> Option<Value> {
+    match self {
+      Self::Null => Some(Value::Null),
+      Self::Bool(inner) => Some(Value::Bool(*inner)),
+      Self::Number(inner) => serde_json::to_value(inner).ok(),
+      Self::String(inner) => Some(Value::String(inner.clone())),
+      Self::Sequence(_) | Self::Mapping(_) => None,
+      Self::Tagged(inner) => inner.value.to_scalar(),
+    }
+  }
+
+  fn to_value(&self) -> Option<Value> {
+    match self {
+      Self::Sequence(inner) => inner
+        .iter()
+        .map(Self::to_value)
+        .collect::<Option<_>>()
+        .map(Value::Array),
+      Self::Mapping(inner) => inner
+        .iter()
+        .map(|(key, value)| Some((key.as_str()?.to_owned(), value.to_value()?)))
+        .collect::<Option<_>>()
+        .map(Value::Object),
+      Self::Tagged(inner) => inner.value.to_value(),
+      _ => self.to_scalar(),
+    }
+  }
+
+  fn object_get(&self, key: &str) -> Option<&Self> {
+    self.as_mapping().and_then(|inner| inner.get(key))
+  }
+
+  fn object_get_mut(&mut self, key: &str) -> Option<&mut Self> {
+    self.as_mapping_mut().and_then(|inner| inner.get_mut(key))
+  }
+
+  fn object_insert(&mut self, key: String, value: Self) -> Option<Self> {
+    self
+      .as_mapping_mut()
+      .and_then(|inner| inner.insert(Self::String(key), value))
+  }
+
+  fn object_remove(&mut self, key: &str) -> Option<Self> {
+    self.as_mapping_mut().and_then(|inner| inner.remove(key))
+  }
+
+  fn array_len(&self) -> usize {
+    self.as_sequence().map_or(0, Vec::len)
+  }
+
+  fn array_get(&self, index: usize) -> Option<&Self> {
+    self.as_sequence().and_then(|inner| inner.get(index))
+  }
+
+  fn array_get_mut(&mut self, index: usize) -> Option<&mut Self> {
+    self
+      .as_sequence_mut()
+      .and_then(|inner| inner.get_mut(index))
+  }
+
+  fn array_insert(&mut self, index: usize, value: Self) {
+    if let Some(inner) = self.as_sequence_mut() {
+      inner.insert(index, value);
+    }
+  }
+
+  fn array_remove(&mut self, index: usize) -> Self {
+    self
+      .as_sequence_mut()
+      .map_or(Self::Null, |inner| inner.remove(index))
+  }
+}
+
+fn number(number: &serde_json::Number) -> Number {
+  if let Some(inner) = number.as_u64() {
+    Number::from(inner)
+  } else if let Some(inner) = number.as_i64() {
+    Number::from(inner)
+  } else {
+    Number::from(number.as_f64().unwrap_or(f64::NAN))
+  }
+}
@@ -2,6 +2,8 @@ use core::fmt::Display;
 use core::fmt::Formatter;
 use core::fmt::Result as FmtResult;
 
+use crate::pointer::Pointer;
+
 pub type Result<T, E = Error> = core::result::Result<T, E>;
 
 #[derive(Debug)]
@@ -10,6 +12,30 @@ pub enum Error {
   InvalidPointer,
   /// The result of a failed `test` operation.
   InvalidTest,
+  /// The result of partitioning a patch with an operation spanning both sides.
+  InvalidPartition,
+  /// The result of an invalid or unrepresentable JSON Merge Patch.
+  InvalidMergePatch,
+  /// The result of converting a JSON value into an incompatible document type.
+  UnsupportedValue,
+  /// The result of checking out a version not present in a history.
+  InvalidVersion,
+  /// The result of applying an operation to a value of the wrong type.
+  InvalidTarget,
+  /// The result of registering a custom operation under a name in use.
+  DuplicateOperation(&'static str),
+  /// The result of reading a malformed JSON Patch document.
+  InvalidPatch(serde_json::Error),
+  /// The result of converting a value from or into its JSON representation.
+  InvalidValue {
+    /// The location of the value that failed to convert.
+    path: Pointer,
+    /// The underlying conversion error.
+    error: serde_json::Error,
+  },
+  /// The result of a failed read from or write to an I/O stream.
+  #[cfg(feature = "std")]
+  Io(::std::io::Error),
 }
 
 impl Display for Error {
@@ -17,9 +43,28 @@ impl Display for Error {
     match self {
       Self::InvalidPointer => f.write_str("Invalid JSON Pointer"),
       Self::InvalidTest => f.write_str("Test Operation Failed"),
+      Self::InvalidPartition => f.write_str("Invalid Patch Partition"),
+      Self::InvalidMergePatch => f.write_str("Invalid Merge Patch"),
+      Self::UnsupportedValue => f.write_str("Unsupported Value"),
+      Self::InvalidVersion => f.write_str("Invalid History Version"),
+      Self::InvalidTarget => f.write_str("Invalid Operation Target"),
+      Self::DuplicateOperation(name) => write!(f, "Duplicate Operation `{}`", name),
+      Self::InvalidPatch(error) => write!(f, "Invalid Patch: {}", error),
+      Self::InvalidValue { path, error } => write!(f, "Invalid Value at `{}`: {}", path, error),
+      #[cfg(feature = "std")]
+      Self::Io(error) => write!(f, "I/O Error: {}", error),
     }
   }
 }
 
 #[cfg(feature = "std")]
-impl ::std::error::Error for Error {}
+impl ::std::error::Error for Error {
+  fn source(&self) -> Option<&(dyn ::std::error::Error + 'static)> {
+    match self {
+      Self::InvalidPatch(error) => Some(error),
+      Self::InvalidValue { error, .. } => Some(error),
+      Self::Io(error) => Some(error),
+      _ => None,
+    }
+  }
+}
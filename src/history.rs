@@ -0,0 +1,311 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::operation::parse_idx;
+use crate::operation::split_at;
+use crate::operation::OpAdd;
+use crate::operation::OpRemove;
+use crate::operation::OpReplace;
+use crate::operation::Operation;
+use crate::patch::prepare;
+use crate::patch::Patch;
+use crate::pointer::is_prefix;
+
+/// The default number of versions between snapshots of a [`History`].
+const INTERVAL: usize = 32;
+
+/// A JSON document along with the patches applied to it.
+///
+/// Every applied patch is stored with its inverse, allowing the document to
+/// be moved to any earlier or later version. Snapshots of the document are
+/// taken periodically to bound the number of patches replayed by
+/// [`checkout`][Self::checkout].
+#[derive(Clone, Debug)]
+pub struct History {
+  base: Value,
+  value: Value,
+  version: usize,
+  entries: Vec<Entry>,
+  snapshots: BTreeMap<usize, Value>,
+  interval: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+  patch: Patch,
+  inverse: Patch,
+}
+
+impl History {
+  /// Creates a new `History` starting at `base`.
+  pub fn new(base: Value) -> Self {
+    Self {
+      value: base.clone(),
+      base,
+      version: 0,
+      entries: Vec::new(),
+      snapshots: BTreeMap::new(),
+      interval: INTERVAL,
+    }
+  }
+
+  /// Sets the number of versions between snapshots; `0` disables snapshots.
+  pub fn with_interval(mut self, interval: usize) -> Self {
+    self.interval = interval;
+    self
+  }
+
+  /// Returns the document the history starts from.
+  pub fn base(&self) -> &Value {
+    &self.base
+  }
+
+  /// Returns the document at the current version.
+  pub fn value(&self) -> &Value {
+    &self.value
+  }
+
+  /// Returns the current version, i.e. the number of patches applied to the
+  /// base document.
+  pub fn version(&self) -> usize {
+    self.version
+  }
+
+  /// Returns the latest version that can be checked out.
+  pub fn latest(&self) -> usize {
+    self.entries.len()
+  }
+
+  /// Returns the patch producing `version` from the version before it.
+  pub fn patch(&self, version: usize) -> Option<&Patch> {
+    self.entry(version).map(|entry| &entry.patch)
+  }
+
+  /// Returns the patch reverting `version` to the version before it.
+  pub fn inverse(&self, version: usize) -> Option<&Patch> {
+    self.entry(version).map(|entry| &entry.inverse)
+  }
+
+  /// Applies `patch` to the current version, returning the new version.
+  ///
+  /// Any versions after the current one are discarded. The document is left
+  /// unchanged if the patch fails.
+  pub fn apply(&mut self, patch: Patch) -> Result<usize> {
+    let inverse: Patch = apply_inverse(&mut self.value, &patch)?;
+
+    let current: usize = self.version;
+
+    self.entries.truncate(current);
+    self.snapshots.retain(|version, _| *version <= current);
+    self.entries.push(Entry { patch, inverse });
+    self.version += 1;
+
+    if self.interval != 0 && self.version.is_multiple_of(self.interval) {
+      self.snapshots.insert(self.version, self.value.clone());
+    }
+
+    Ok(self.version)
+  }
+
+  /// Reverts the current version, returning `false` if there is nothing to
+  /// undo.
+  pub fn undo(&mut self) -> Result<bool> {
+    if self.version == 0 {
+      return Ok(false);
+    }
+
+    self.checkout(self.version - 1)?;
+
+    Ok(true)
+  }
+
+  /// Reapplies the version after the current one, returning `false` if there
+  /// is nothing to redo.
+  pub fn redo(&mut self) -> Result<bool> {
+    if self.version == self.latest() {
+      return Ok(false);
+    }
+
+    self.checkout(self.version + 1)?;
+
+    Ok(true)
+  }
+
+  /// Moves the document to `version`.
+  ///
+  /// Fails with [`Error::InvalidVersion`] if `version` is after the latest
+  /// version.
+  pub fn checkout(&mut self, version: usize) -> Result<()> {
+    let (start, snapshot): (usize, &Value) = self.snapshot(version)?;
+
+    if version - start < self.version.abs_diff(version) {
+      self.value = snapshot.clone();
+      self.version = start;
+    }
+
+    while self.version > version {
+      self.entries[self.version - 1]
+        .inverse
+        .clone()
+        .apply_mut(&mut self.value)?;
+      self.version -= 1;
+    }
+
+    while self.version < version {
+      self.entries[self.version]
+        .patch
+        .clone()
+        .apply_mut(&mut self.value)?;
+      self.version += 1;
+    }
+
+    Ok(())
+  }
+
+  /// Returns the document at `version` without moving the current version.
+  pub fn get(&self, version: usize) -> Result<Value> {
+    let (start, snapshot): (usize, &Value) = self.snapshot(version)?;
+    let mut value: Value = snapshot.clone();
+
+    for entry in &self.entries[start..version] {
+      entry.patch.clone().apply_mut(&mut value)?;
+    }
+
+    Ok(value)
+  }
+
+  fn entry(&self, version: usize) -> Option<&Entry> {
+    version
+      .checked_sub(1)
+      .and_then(|index| self.entries.get(index))
+  }
+
+  /// Returns the latest snapshot at or before `version`.
+  fn snapshot(&self, version: usize) -> Result<(usize, &Value)> {
+    if version > self.latest() {
+      return Err(Error::InvalidVersion);
+    }
+
+    Ok(
+      self
+        .snapshots
+        .range(..=version)
+        .next_back()
+        .map(|(version, value)| (*version, value))
+        .unwrap_or((0, &self.base)),
+    )
+  }
+}
+
+/// Applies `patch` to `value`, returning a patch that reverts it.
+///
+/// `value` is left unchanged if the patch fails.
+fn apply_inverse(value: &mut Value, patch: &Patch) -> Result<Patch> {
+  let mut inverse: Vec<Operation> = Vec::new();
+
+  if !patch.is_empty() && !(value.is_object() || value.is_array()) {
+    inverse.push(replace("", value.clone()));
+    prepare(value)?;
+  }
+
+  for operation in patch {
+    if let Err(error) = apply_operation(value, operation.clone(), &mut inverse) {
+      for operation in inverse.into_iter().rev() {
+        operation.apply(value)?;
+      }
+
+      return Err(error);
+    }
+  }
+
+  inverse.reverse();
+
+  Ok(inverse.into())
+}
+
+fn apply_operation(
+  value: &mut Value,
+  operation: Operation,
+  inverse: &mut Vec<Operation>,
+) -> Result<()> {
+  let revert: Operation = match operation {
+    Operation::Add(ref op) => revert_add(value, &op.path)?,
+    Operation::Copy(ref op) => revert_add(value, &op.path)?,
+    Operation::Remove(ref op) => Operation::Add(OpAdd {
+      path: op.path.clone(),
+      value: lookup(value, &op.path)?.clone(),
+    }),
+    Operation::Replace(ref op) => replace(&op.path, lookup(value, &op.path)?.clone()),
+    Operation::Move(op) if op.from == op.path => return Operation::Move(op).apply(value),
+    Operation::Move(op) => {
+      // Record a move as the equivalent "remove" followed by an "add" so each
+      // half is reverted against the document it was applied to.
+      if is_prefix(&op.from, &op.path) {
+        return Err(Error::InvalidPointer);
+      }
+
+      let moved: Value = lookup(value, &op.from)?.clone();
+
+      apply_operation(
+        value,
+        Operation::Remove(OpRemove { path: op.from }),
+        inverse,
+      )?;
+
+      return apply_operation(
+        value,
+        Operation::Add(OpAdd {
+          path: op.path,
+          value: moved,
+        }),
+        inverse,
+      );
+    }
+    Operation::Test(op) => return Operation::Test(op).apply(value),
+    ref operation => replace(operation.path(), lookup(value, operation.path())?.clone()),
+  };
+
+  operation.apply(value)?;
+  inverse.push(revert);
+
+  Ok(())
+}
+
+/// Returns an operation reverting an "add" of any value at `path`.
+fn revert_add(value: &Value, path: &str) -> Result<Operation> {
+  if path.is_empty() {
+    return Ok(replace(path, value.clone()));
+  }
+
+  let (ptr, key) = split_at(path)?;
+
+  match lookup(value, ptr)? {
+    Value::Object(object) => match object.get(key.as_ref()) {
+      Some(value) => Ok(replace(path, value.clone())),
+      None => Ok(remove(path)),
+    },
+    Value::Array(array) if key == "-" => Ok(remove(&format!("{}/{}", ptr, array.len()))),
+    Value::Array(array) => {
+      let index: usize = parse_idx(&key, array.len() + 1)?;
+      Ok(remove(&format!("{}/{}", ptr, index)))
+    }
+    _ => Err(Error::InvalidPointer),
+  }
+}
+
+fn lookup<'a>(value: &'a Value, path: &str) -> Result<&'a Value> {
+  value.pointer(path).ok_or(Error::InvalidPointer)
+}
+
+fn remove(path: &str) -> Operation {
+  Operation::Remove(OpRemove { path: path.into() })
+}
+
+fn replace(path: &str, value: Value) -> Operation {
+  Operation::Replace(OpReplace {
+    path: path.into(),
+    value,
+  })
+}
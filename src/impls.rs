@@ -0,0 +1,289 @@
+#[cfg(feature = "std")]
+use core::hash::BuildHasher;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::derive::add;
+use crate::derive::copy;
+use crate::derive::get;
+use crate::derive::move_;
+use crate::derive::remove;
+use crate::derive::replace;
+use crate::derive::set;
+use crate::derive::split;
+use crate::derive::test;
+use crate::derive::Field;
+use crate::derive::Fields;
+use crate::error::Error;
+use crate::error::Result;
+use crate::operation::parse_idx;
+use crate::operation::OpAdd;
+use crate::operation::OpCopy;
+use crate::operation::OpMove;
+use crate::operation::OpRemove;
+use crate::operation::OpReplace;
+use crate::operation::OpTest;
+use crate::operation::Operation;
+use crate::traits::CanPatch;
+
+macro_rules! impl_can_patch {
+  () => {
+    fn patch_copy(&mut self, op: OpCopy) -> Result<()> {
+      copy(self, op)
+    }
+
+    fn patch_move(&mut self, op: OpMove) -> Result<()> {
+      move_(self, op)
+    }
+
+    fn patch_replace(&mut self, op: OpReplace) -> Result<()> {
+      replace(self, op)
+    }
+
+    fn patch_test(&mut self, op: OpTest) -> Result<()> {
+      test(self, op)
+    }
+
+    fn patch_get(&self, path: &str) -> Result<Value> {
+      get(self, path)
+    }
+  };
+}
+
+macro_rules! impl_scalar {
+  ($($ty:ty),* $(,)?) => {
+    $(
+      impl Fields for $ty {
+        fn field(&self, path: &str) -> Result<Field<&dyn CanPatch>> {
+          root(path)
+        }
+
+        fn field_mut(&mut self, path: &str) -> Result<Field<&mut dyn CanPatch>> {
+          root(path)
+        }
+      }
+
+      impl CanPatch for $ty {
+        impl_can_patch!();
+
+        fn patch_add(&mut self, op: OpAdd) -> Result<()> {
+          add(self, op)
+        }
+
+        fn patch_remove(&mut self, op: OpRemove) -> Result<()> {
+          remove(self, op)
+        }
+      }
+    )*
+  };
+}
+
+impl_scalar!(
+  bool, char, String, f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize,
+);
+
+impl<T> Fields for Option<T>
+where
+  T: CanPatch,
+{
+  fn field(&self, path: &str) -> Result<Field<&dyn CanPatch>> {
+    match self {
+      _ if path.is_empty() => Ok(Field::Root),
+      Some(inner) => Ok(Field::Field(inner, path.into())),
+      None => Err(Error::InvalidPointer),
+    }
+  }
+
+  fn field_mut(&mut self, path: &str) -> Result<Field<&mut dyn CanPatch>> {
+    match self {
+      _ if path.is_empty() => Ok(Field::Root),
+      Some(inner) => Ok(Field::Field(inner, path.into())),
+      None => Err(Error::InvalidPointer),
+    }
+  }
+}
+
+impl<T> CanPatch for Option<T>
+where
+  T: CanPatch + Serialize + DeserializeOwned,
+{
+  impl_can_patch!();
+
+  fn patch_add(&mut self, op: OpAdd) -> Result<()> {
+    add(self, op)
+  }
+
+  fn patch_remove(&mut self, op: OpRemove) -> Result<()> {
+    match self {
+      Some(_) if op.path.is_empty() => {
+        *self = None;
+        Ok(())
+      }
+      _ => remove(self, op),
+    }
+  }
+}
+
+impl<T> Fields for Vec<T>
+where
+  T: CanPatch,
+{
+  fn field(&self, path: &str) -> Result<Field<&dyn CanPatch>> {
+    match split(path)? {
+      Some((token, path)) => Ok(Field::Field(&self[parse_idx(&token, self.len())?], path)),
+      None => Ok(Field::Root),
+    }
+  }
+
+  fn field_mut(&mut self, path: &str) -> Result<Field<&mut dyn CanPatch>> {
+    match split(path)? {
+      Some((token, path)) => {
+        let index: usize = parse_idx(&token, self.len())?;
+        Ok(Field::Field(&mut self[index], path))
+      }
+      None => Ok(Field::Root),
+    }
+  }
+}
+
+impl<T> CanPatch for Vec<T>
+where
+  T: CanPatch + Serialize + DeserializeOwned,
+{
+  impl_can_patch!();
+
+  fn patch_add(&mut self, op: OpAdd) -> Result<()> {
+    match split(&op.path)? {
+      Some((token, path)) if path.is_empty() => {
+        let index: usize = if token == "-" {
+          self.len()
+        } else {
+          parse_idx(&token, self.len() + 1)?
+        };
+
+        let mut value: Option<T> = None;
+        set(&mut value, op.value)?;
+        self.insert(index, value.ok_or(Error::InvalidPointer)?);
+
+        Ok(())
+      }
+      _ => add(self, op),
+    }
+  }
+
+  fn patch_remove(&mut self, op: OpRemove) -> Result<()> {
+    match split(&op.path)? {
+      Some((token, path)) if path.is_empty() => {
+        self.remove(parse_idx(&token, self.len())?);
+        Ok(())
+      }
+      _ => remove(self, op),
+    }
+  }
+}
+
+macro_rules! impl_map {
+  ($ty:ident $(, $param:ident: [$($bound:tt)*])*) => {
+    impl<T $(, $param)*> Fields for $ty<String, T $(, $param)*>
+    where
+      T: CanPatch,
+      $($param: $($bound)*,)*
+    {
+      fn field(&self, path: &str) -> Result<Field<&dyn CanPatch>> {
+        match split(path)? {
+          Some((token, path)) => match self.get(&*token) {
+            Some(value) => Ok(Field::Field(value, path)),
+            None => Err(Error::InvalidPointer),
+          },
+          None => Ok(Field::Root),
+        }
+      }
+
+      fn field_mut(&mut self, path: &str) -> Result<Field<&mut dyn CanPatch>> {
+        match split(path)? {
+          Some((token, path)) => match self.get_mut(&*token) {
+            Some(value) => Ok(Field::Field(value, path)),
+            None => Err(Error::InvalidPointer),
+          },
+          None => Ok(Field::Root),
+        }
+      }
+    }
+
+    impl<T $(, $param)*> CanPatch for $ty<String, T $(, $param)*>
+    where
+      T: CanPatch + Serialize + DeserializeOwned,
+      $($param: $($bound)*,)*
+    {
+      impl_can_patch!();
+
+      fn patch_add(&mut self, op: OpAdd) -> Result<()> {
+        match split(&op.path)? {
+          Some((token, path)) if path.is_empty() => {
+            let mut value: Option<T> = None;
+            set(&mut value, op.value)?;
+            self.insert(token.into_owned(), value.ok_or(Error::InvalidPointer)?);
+
+            Ok(())
+          }
+          _ => add(self, op),
+        }
+      }
+
+      fn patch_remove(&mut self, op: OpRemove) -> Result<()> {
+        match split(&op.path)? {
+          Some((token, path)) if path.is_empty() => {
+            self.remove(&*token).map(|_| ()).ok_or(Error::InvalidPointer)
+          }
+          _ => remove(self, op),
+        }
+      }
+    }
+  };
+}
+
+impl_map!(BTreeMap);
+#[cfg(feature = "std")]
+impl_map!(HashMap, S: [BuildHasher + Default]);
+
+impl CanPatch for Value {
+  fn patch_add(&mut self, op: OpAdd) -> Result<()> {
+    Operation::Add(op).apply(self)
+  }
+
+  fn patch_copy(&mut self, op: OpCopy) -> Result<()> {
+    Operation::Copy(op).apply(self)
+  }
+
+  fn patch_move(&mut self, op: OpMove) -> Result<()> {
+    Operation::Move(op).apply(self)
+  }
+
+  fn patch_remove(&mut self, op: OpRemove) -> Result<()> {
+    Operation::Remove(op).apply(self)
+  }
+
+  fn patch_replace(&mut self, op: OpReplace) -> Result<()> {
+    Operation::Replace(op).apply(self)
+  }
+
+  fn patch_test(&mut self, op: OpTest) -> Result<()> {
+    Operation::Test(op).apply(self)
+  }
+
+  fn patch_get(&self, path: &str) -> Result<Value> {
+    self.pointer(path).cloned().ok_or(Error::InvalidPointer)
+  }
+}
+
+fn root<T>(path: &str) -> Result<Field<T>> {
+  if path.is_empty() {
+    Ok(Field::Root)
+  } else {
+    Err(Error::InvalidPointer)
+  }
+}
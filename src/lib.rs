@@ -1,20 +1,104 @@
 #[macro_use]
 extern crate serde;
 
+mod compiled;
+mod derive;
+mod document;
+#[cfg(feature = "toml")]
+mod document_toml;
+mod document_tree;
+#[cfg(feature = "yaml")]
+mod document_yaml;
 mod error;
+mod history;
+mod impls;
+#[cfg(feature = "std")]
+mod log;
 mod merge;
 mod operation;
+mod overlay;
+#[cfg(feature = "rayon")]
+mod parallel;
 mod patch;
+mod patch_ref;
+mod pointer;
+mod strategic;
+#[cfg(feature = "std")]
+mod stream;
+mod text;
 mod traits;
+mod typed;
+
+pub use self::compiled::CompiledPatch;
+
+pub use self::document::Document;
+pub use self::document::NodeKind;
+pub use self::document_tree::TreeValue;
 
 pub use self::error::Error;
 pub use self::error::Result;
 
+pub use self::history::History;
+
+#[cfg(feature = "std")]
+pub use self::log::LogEntry;
+#[cfg(feature = "std")]
+pub use self::log::LogReader;
+#[cfg(feature = "std")]
+pub use self::log::LogWriter;
+
+pub use self::merge::merge_diff;
+pub use self::merge::merge_document;
 pub use self::merge::merge_mut;
 pub use self::merge::merge_ref;
+pub use self::merge::merge_with;
+pub use self::merge::MergeArrays;
+pub use self::merge::MergeNulls;
+pub use self::merge::MergeOptions;
+pub use self::merge::MergePatch;
 
+#[cfg(feature = "std")]
+pub use self::operation::register;
+pub use self::operation::Condition;
+pub use self::operation::Extension;
+pub use self::operation::OpAdd;
+pub use self::operation::OpAddToSet;
+pub use self::operation::OpCopy;
+pub use self::operation::OpCustom;
+pub use self::operation::OpMove;
+pub use self::operation::OpNumeric;
+pub use self::operation::OpPull;
+pub use self::operation::OpRemove;
+pub use self::operation::OpReplace;
+pub use self::operation::OpStrDel;
+pub use self::operation::OpStrIns;
+pub use self::operation::OpTest;
 pub use self::operation::Operation;
+pub use self::operation::OperationRef;
+pub use self::operation::ValueType;
+
+pub use self::overlay::Overlay;
 
 pub use self::patch::Patch;
+pub use self::patch_ref::PatchRef;
+
+pub use self::pointer::Pointer;
+
+#[cfg(feature = "std")]
+pub use self::stream::PatchReader;
+
+pub use self::strategic::merge_strategic;
+pub use self::strategic::MergeKeys;
 
 pub use self::traits::CanPatch;
+
+#[cfg(feature = "derive")]
+pub use json_patch_derive::JsonPatch;
+
+#[doc(hidden)]
+pub mod __private {
+  pub use crate::derive::*;
+  pub use serde::de::DeserializeOwned;
+  pub use serde::Serialize;
+  pub use serde_json::Value;
+}
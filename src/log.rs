@@ -0,0 +1,170 @@
+use core::str::FromStr;
+use serde_json::Value;
+use std::io::BufRead;
+use std::io::Lines;
+use std::io::Write;
+
+use crate::document::Document;
+use crate::error::Error;
+use crate::error::Result;
+use crate::operation::Operation;
+use crate::patch::Patch;
+
+/// An entry of a newline-delimited JSON Patch log.
+///
+/// Each line of a log holds one entry, written as an object with a `patch`
+/// member and optional `seq` and `timestamp` members:
+///
+/// ```text
+/// {"seq":1,"timestamp":"2021-01-01T00:00:00Z","patch":[{"op":"add","path":"/a","value":1}]}
+/// ```
+///
+/// When reading, a line may also hold a bare patch array, a bare operation
+/// object, or an entry whose `patch` member is a single operation.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LogEntry {
+  /// The sequence number of the entry.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub seq: Option<u64>,
+  /// The time at which the entry was recorded, e.g. an RFC 3339 string.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timestamp: Option<String>,
+  /// The operations of the entry.
+  pub patch: Patch,
+}
+
+impl LogEntry {
+  /// Creates a new `LogEntry` without a sequence number or timestamp.
+  pub fn new(patch: impl Into<Patch>) -> Self {
+    Self {
+      seq: None,
+      timestamp: None,
+      patch: patch.into(),
+    }
+  }
+
+  /// Sets the sequence number of the entry.
+  pub fn with_seq(mut self, seq: u64) -> Self {
+    self.seq = Some(seq);
+    self
+  }
+
+  /// Sets the timestamp of the entry.
+  pub fn with_timestamp(mut self, timestamp: impl Into<String>) -> Self {
+    self.timestamp = Some(timestamp.into());
+    self
+  }
+}
+
+impl FromStr for LogEntry {
+  type Err = Error;
+
+  fn from_str(line: &str) -> Result<Self, Self::Err> {
+    #[derive(Deserialize)]
+    struct Framed {
+      seq: Option<u64>,
+      timestamp: Option<String>,
+      patch: Value,
+    }
+
+    let value: Value = serde_json::from_str(line).map_err(Error::InvalidPatch)?;
+
+    if value.get("op").is_some() || value.is_array() {
+      return patch(value).map(Self::new);
+    }
+
+    let framed: Framed = serde_json::from_value(value).map_err(Error::InvalidPatch)?;
+
+    Ok(Self {
+      seq: framed.seq,
+      timestamp: framed.timestamp,
+      patch: patch(framed.patch)?,
+    })
+  }
+}
+
+fn patch(value: Value) -> Result<Patch> {
+  if value.is_array() {
+    serde_json::from_value(value).map_err(Error::InvalidPatch)
+  } else {
+    serde_json::from_value::<Operation>(value)
+      .map(|operation| vec![operation].into())
+      .map_err(Error::InvalidPatch)
+  }
+}
+
+/// Writes [`LogEntry`]s as newline-delimited JSON.
+#[derive(Debug)]
+pub struct LogWriter<W> {
+  writer: W,
+}
+
+impl<W: Write> LogWriter<W> {
+  /// Creates a new `LogWriter` writing entries to `writer`.
+  pub fn new(writer: W) -> Self {
+    Self { writer }
+  }
+
+  /// Appends `entry` to the log as a single line.
+  pub fn write(&mut self, entry: &LogEntry) -> Result<()> {
+    // Serialize the entry up front so a failure never leaves a partial line.
+    let mut line: Vec<u8> = serde_json::to_vec(entry).map_err(Error::InvalidPatch)?;
+
+    line.push(b'\n');
+
+    self.writer.write_all(&line).map_err(Error::Io)
+  }
+
+  /// Flushes the underlying writer.
+  pub fn flush(&mut self) -> Result<()> {
+    self.writer.flush().map_err(Error::Io)
+  }
+
+  /// Consumes the `LogWriter` and returns the underlying writer.
+  pub fn into_inner(self) -> W {
+    self.writer
+  }
+}
+
+/// Reads [`LogEntry`]s from newline-delimited JSON.
+///
+/// Blank lines are skipped.
+#[derive(Debug)]
+pub struct LogReader<R> {
+  lines: Lines<R>,
+}
+
+impl<R: BufRead> LogReader<R> {
+  /// Creates a new `LogReader` reading entries from `reader`.
+  pub fn new(reader: R) -> Self {
+    Self {
+      lines: reader.lines(),
+    }
+  }
+
+  /// Applies every remaining entry of the log to `output` in order, returning
+  /// the number of entries applied.
+  ///
+  /// Each entry is applied as a whole or not at all, and entries applied
+  /// before a failure are kept.
+  pub fn replay<D: Document>(self, output: &mut D) -> Result<usize> {
+    self.into_iter().try_fold(0, |count, entry| {
+      *output = entry?.patch.apply_ref(output)?;
+      Ok(count + 1)
+    })
+  }
+}
+
+impl<R: BufRead> Iterator for LogReader<R> {
+  type Item = Result<LogEntry>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      match self.lines.next()? {
+        Ok(line) if line.trim().is_empty() => continue,
+        Ok(line) => return Some(line.parse()),
+        Err(error) => return Some(Err(Error::Io(error))),
+      }
+    }
+  }
+}
@@ -1,5 +1,108 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Map;
 use serde_json::Value;
 
+use crate::document::Document;
+use crate::document::NodeKind;
+use crate::error::Error;
+use crate::error::Result;
+use crate::operation::OpAdd;
+use crate::operation::OpRemove;
+use crate::operation::OpReplace;
+use crate::operation::Operation;
+use crate::patch::Patch;
+use crate::pointer::Pointer;
+use crate::typed::apply_typed;
+
+/// A JSON Merge Patch document.
+///
+/// [More Info](https://tools.ietf.org/html/rfc7396)
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[repr(transparent)]
+#[serde(transparent)]
+pub struct MergePatch(Value);
+
+impl MergePatch {
+  pub const fn new(value: Value) -> Self {
+    Self(value)
+  }
+
+  pub fn as_value(&self) -> &Value {
+    &self.0
+  }
+
+  pub fn into_value(self) -> Value {
+    self.0
+  }
+
+  pub fn apply_ref(&self, output: &Value) -> Value {
+    merge_ref(output, &self.0)
+  }
+
+  pub fn apply_mut(&self, output: &mut Value) {
+    merge_mut(output, &self.0)
+  }
+
+  pub fn apply_document<D: Document>(&self, output: &mut D) -> Result<()> {
+    merge_document(output, &self.0)
+  }
+
+  pub fn apply_with(&self, output: &mut Value, options: &MergeOptions) {
+    merge_with(output, &self.0, options)
+  }
+
+  /// Applies the merge patch to a typed value via its JSON representation.
+  ///
+  /// `value` is left unchanged if the patched document cannot be deserialized
+  /// into `T`.
+  pub fn apply_to<T>(&self, value: &mut T) -> Result<()>
+  where
+    T: Serialize + DeserializeOwned,
+  {
+    apply_typed(value, |json| {
+      merge_mut(json, &self.0);
+      Ok(())
+    })
+  }
+
+  /// Returns a merge patch equivalent to applying `self` followed by `other`.
+  ///
+  /// Fails with [`Error::InvalidMergePatch`] if the combined result cannot be
+  /// expressed as a single merge patch, e.g. when `self` replaces a member
+  /// with a non-object value that `other` then merges an object into.
+  pub fn compose(&self, other: &Self) -> Result<Self> {
+    compose(&self.0, &other.0).map(Self)
+  }
+
+  /// Converts the merge patch into an equivalent JSON Patch relative to `base`.
+  pub fn to_patch(&self, base: &Value) -> Patch {
+    let mut output: Vec<Operation> = Vec::new();
+
+    operations(base, &self.0, &mut Pointer::root(), &mut output);
+
+    output.into()
+  }
+}
+
+impl From<Value> for MergePatch {
+  fn from(other: Value) -> Self {
+    Self(other)
+  }
+}
+
+impl From<MergePatch> for Value {
+  fn from(other: MergePatch) -> Self {
+    other.0
+  }
+}
+
+impl AsRef<Value> for MergePatch {
+  fn as_ref(&self) -> &Value {
+    &self.0
+  }
+}
+
 pub fn merge_ref(value: &Value, patch: &Value) -> Value {
   let mut value: Value = value.clone();
 
@@ -28,3 +131,259 @@ pub fn merge_mut(value: &mut Value, patch: &Value) {
     }
   }
 }
+
+/// Applies a merge patch to a [`Document`].
+///
+/// This is equivalent to [`merge_mut`] for documents other than `Value` and
+/// fails with [`Error::UnsupportedValue`] if the patched document cannot be
+/// represented by `D`.
+pub fn merge_document<D: Document>(value: &mut D, patch: &Value) -> Result<()> {
+  match patch {
+    Value::Object(patch) => {
+      if value.kind() != NodeKind::Object {
+        *value = D::from_value(Value::Object(Map::new()))?;
+      }
+
+      for (name, other) in patch {
+        if other.is_null() {
+          value.object_remove(name);
+        } else if let Some(value) = value.object_get_mut(name) {
+          merge_document(value, other)?;
+        } else {
+          value.object_insert(name.clone(), D::from_value(merge_ref(&Value::Null, other))?);
+        }
+      }
+
+      Ok(())
+    }
+    _ => {
+      *value = D::from_value(patch.clone())?;
+      Ok(())
+    }
+  }
+}
+
+fn compose(lhs: &Value, rhs: &Value) -> Result<Value> {
+  match (lhs, rhs) {
+    (_, rhs) if !rhs.is_object() => Ok(rhs.clone()),
+    (Value::Object(lhs), Value::Object(rhs)) => {
+      let mut output: Map<String, Value> = lhs.clone();
+
+      for (name, other) in rhs {
+        match output.get_mut(name) {
+          Some(value) if other.is_null() => *value = Value::Null,
+          Some(value) => *value = compose(value, other)?,
+          None => {
+            output.insert(name.clone(), other.clone());
+          }
+        }
+      }
+
+      Ok(Value::Object(output))
+    }
+    (_, _) => Err(Error::InvalidMergePatch),
+  }
+}
+
+/// The handling of `null` values in a merge patch.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MergeNulls {
+  /// `null` removes the target member (RFC 7396).
+  Delete,
+  /// `null` sets the target member to `null`; `delete` removes it instead.
+  Set { delete: Value },
+}
+
+/// The handling of arrays in a merge patch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MergeArrays {
+  /// The target array is replaced (RFC 7396).
+  Replace,
+  /// The patch array is appended to the target array.
+  Append,
+  /// Elements of the patch array not in the target array are appended.
+  Union,
+}
+
+/// Options for applying a non-standard merge patch with [`merge_with`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergeOptions {
+  /// The handling of `null` values in the patch.
+  pub nulls: MergeNulls,
+  /// The handling of arrays in both the patch and the target.
+  pub arrays: MergeArrays,
+  /// If `true`, an object patch leaves an existing non-object target unchanged
+  /// instead of replacing it with an empty object.
+  pub preserve: bool,
+}
+
+impl MergeOptions {
+  fn is_delete(&self, value: &Value) -> bool {
+    match self.nulls {
+      MergeNulls::Delete => value.is_null(),
+      MergeNulls::Set { ref delete } => value == delete,
+    }
+  }
+}
+
+impl Default for MergeOptions {
+  fn default() -> Self {
+    Self {
+      nulls: MergeNulls::Delete,
+      arrays: MergeArrays::Replace,
+      preserve: false,
+    }
+  }
+}
+
+/// Applies a merge patch to `value` with the given non-standard `options`.
+///
+/// With default options this is equivalent to [`merge_mut`].
+pub fn merge_with(value: &mut Value, patch: &Value, options: &MergeOptions) {
+  match (value, patch) {
+    (Value::Object(value), Value::Object(patch)) => {
+      for (name, other) in patch {
+        if options.is_delete(other) {
+          value.remove(name.as_str());
+        } else {
+          merge_with(
+            value.entry(name.as_str()).or_insert(Value::Null),
+            other,
+            options,
+          );
+        }
+      }
+    }
+    (Value::Array(value), Value::Array(patch)) => match options.arrays {
+      MergeArrays::Replace => {
+        *value = patch.clone();
+      }
+      MergeArrays::Append => {
+        value.extend(patch.iter().cloned());
+      }
+      MergeArrays::Union => {
+        for item in patch {
+          if !value.contains(item) {
+            value.push(item.clone());
+          }
+        }
+      }
+    },
+    (value @ Value::Object(_), _) => {
+      *value = patch.clone();
+    }
+    (value, Value::Object(_)) if options.preserve && !value.is_null() => {}
+    (value, Value::Object(_)) => {
+      *value = Value::Object(Default::default());
+      merge_with(value, patch, options);
+    }
+    (value, _) => {
+      *value = patch.clone();
+    }
+  }
+}
+
+fn operations(value: &Value, patch: &Value, path: &mut Pointer, output: &mut Vec<Operation>) {
+  match (value, patch) {
+    (Value::Object(value), Value::Object(patch)) => {
+      for (name, other) in patch {
+        path.push(name);
+
+        match value.get(name) {
+          Some(_) if other.is_null() => {
+            output.push(Operation::Remove(OpRemove {
+              path: path.to_string(),
+            }));
+          }
+          Some(value) => {
+            operations(value, other, path, output);
+          }
+          None if other.is_null() => {}
+          None => {
+            output.push(Operation::Add(OpAdd {
+              path: path.to_string(),
+              value: merge_ref(&Value::Null, other),
+            }));
+          }
+        }
+
+        *path = path.parent().unwrap_or_default();
+      }
+    }
+    (value, patch) => {
+      let patch: Value = merge_ref(value, patch);
+
+      if *value != patch {
+        output.push(Operation::Replace(OpReplace {
+          path: path.to_string(),
+          value: patch,
+        }));
+      }
+    }
+  }
+}
+
+/// Creates a merge patch that transforms `from` into `to`.
+///
+/// Fails with [`Error::InvalidMergePatch`] if `to` contains an object member
+/// with an explicit `null` value, which a merge patch cannot express.
+pub fn merge_diff(from: &Value, to: &Value) -> Result<MergePatch> {
+  diff(from, to)
+    .map(|patch| patch.unwrap_or_else(|| Value::Object(Map::new())))
+    .map(MergePatch)
+}
+
+fn diff(from: &Value, to: &Value) -> Result<Option<Value>> {
+  match (from, to) {
+    (Value::Object(from), Value::Object(to)) => {
+      let mut patch: Map<String, Value> = Map::new();
+
+      for name in from.keys() {
+        if !to.contains_key(name) {
+          patch.insert(name.clone(), Value::Null);
+        }
+      }
+
+      for (name, other) in to {
+        // A null member cannot be expressed by a merge patch unless it is
+        // unchanged.
+        if other.is_null() {
+          match from.get(name) {
+            Some(Value::Null) => continue,
+            Some(_) | None => return Err(Error::InvalidMergePatch),
+          }
+        }
+
+        if let Some(value) = from.get(name) {
+          if let Some(value) = diff(value, other)? {
+            patch.insert(name.clone(), value);
+          }
+        } else {
+          patch.insert(name.clone(), checked(other)?.clone());
+        }
+      }
+
+      if patch.is_empty() {
+        Ok(None)
+      } else {
+        Ok(Some(Value::Object(patch)))
+      }
+    }
+    (from, to) if from == to => Ok(None),
+    (_, to) => checked(to).map(|to| Some(to.clone())),
+  }
+}
+
+fn checked(value: &Value) -> Result<&Value> {
+  if let Value::Object(inner) = value {
+    for value in inner.values() {
+      if value.is_null() {
+        return Err(Error::InvalidMergePatch);
+      }
+
+      checked(value)?;
+    }
+  }
+
+  Ok(value)
+}
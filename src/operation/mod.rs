@@ -1,15 +1,25 @@
 mod op;
 mod op_add;
+mod op_array;
 mod op_copy;
+mod op_custom;
 mod op_move;
+mod op_numeric;
+mod op_ref;
 mod op_remove;
 mod op_replace;
+mod op_string;
 mod op_test;
 
 pub use self::op::*;
 pub use self::op_add::*;
+pub use self::op_array::*;
 pub use self::op_copy::*;
+pub use self::op_custom::*;
 pub use self::op_move::*;
+pub use self::op_numeric::*;
+pub use self::op_ref::*;
 pub use self::op_remove::*;
 pub use self::op_replace::*;
+pub use self::op_string::*;
 pub use self::op_test::*;
@@ -1,17 +1,47 @@
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::marker::PhantomData;
 use core::mem;
+use serde::de::value::MapAccessDeserializer;
+use serde::de::DeserializeSeed;
+use serde::de::Error as _;
+use serde::de::IntoDeserializer;
+use serde::de::MapAccess;
+use serde::de::Visitor;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde_json::Map;
 use serde_json::Value;
+use std::borrow::Cow;
+use std::vec::IntoIter;
 
+use crate::document::Document;
+use crate::document::NodeKind;
 use crate::error::Error;
 use crate::error::Result;
+use crate::operation::parse_custom;
+use crate::operation::Arithmetic;
 use crate::operation::OpAdd;
+use crate::operation::OpAddToSet;
 use crate::operation::OpCopy;
+use crate::operation::OpCustom;
 use crate::operation::OpMove;
+use crate::operation::OpNumeric;
+use crate::operation::OpPull;
 use crate::operation::OpRemove;
 use crate::operation::OpReplace;
+use crate::operation::OpStrDel;
+use crate::operation::OpStrIns;
 use crate::operation::OpTest;
+use crate::operation::STANDARD;
+use crate::pointer::tokens;
+use crate::pointer::unescape;
 
 /// A JSON Patch operation.
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+///
+/// Extension operations such as `inc` are always applied, but only read from
+/// a patch with the `extensions` feature.
+#[derive(Clone, Debug, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase", tag = "op")]
 pub enum Operation {
   Add(OpAdd),
@@ -20,53 +50,289 @@ pub enum Operation {
   Move(OpMove),
   Copy(OpCopy),
   Test(OpTest),
+  Inc(OpNumeric),
+  Dec(OpNumeric),
+  Mul(OpNumeric),
+  Min(OpNumeric),
+  Max(OpNumeric),
+  #[serde(rename = "str_ins")]
+  StrIns(OpStrIns),
+  #[serde(rename = "str_del")]
+  StrDel(OpStrDel),
+  #[serde(rename = "add_to_set")]
+  AddToSet(OpAddToSet),
+  Pull(OpPull),
+  /// A custom operation registered with [`register`][crate::register].
+  #[serde(untagged)]
+  Custom(OpCustom),
+}
+
+impl<'de> Deserialize<'de> for Operation {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    deserializer.deserialize_map(OperationVisitor)
+  }
+}
+
+struct OperationVisitor;
+
+impl<'de> Visitor<'de> for OperationVisitor {
+  type Value = Operation;
+
+  fn expecting(&self, f: &mut Formatter) -> FmtResult {
+    f.write_str("a JSON Patch operation")
+  }
+
+  fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+  where
+    A: MapAccess<'de>,
+  {
+    // Only the members preceding `op` are buffered, the rest are read by the
+    // operation itself.
+    let mut buffered: Vec<(String, Value)> = Vec::new();
+
+    let name: String = loop {
+      match map.next_key::<String>()? {
+        Some(key) if key == "op" => break map.next_value()?,
+        Some(key) => buffered.push((key, map.next_value()?)),
+        None => return Err(A::Error::missing_field("op")),
+      }
+    };
+
+    let members: Members<'de, A> = Members {
+      buffered: buffered.into_iter(),
+      value: None,
+      map,
+      marker: PhantomData,
+    };
+
+    let deserializer: MapAccessDeserializer<Members<'de, A>> = MapAccessDeserializer::new(members);
+
+    match name.as_str() {
+      "add" => Deserialize::deserialize(deserializer).map(Operation::Add),
+      "remove" => Deserialize::deserialize(deserializer).map(Operation::Remove),
+      "replace" => Deserialize::deserialize(deserializer).map(Operation::Replace),
+      "move" => Deserialize::deserialize(deserializer).map(Operation::Move),
+      "copy" => Deserialize::deserialize(deserializer).map(Operation::Copy),
+      "test" => Deserialize::deserialize(deserializer).map(Operation::Test),
+      #[cfg(feature = "extensions")]
+      "inc" => Deserialize::deserialize(deserializer).map(Operation::Inc),
+      #[cfg(feature = "extensions")]
+      "dec" => Deserialize::deserialize(deserializer).map(Operation::Dec),
+      #[cfg(feature = "extensions")]
+      "mul" => Deserialize::deserialize(deserializer).map(Operation::Mul),
+      #[cfg(feature = "extensions")]
+      "min" => Deserialize::deserialize(deserializer).map(Operation::Min),
+      #[cfg(feature = "extensions")]
+      "max" => Deserialize::deserialize(deserializer).map(Operation::Max),
+      #[cfg(feature = "extensions")]
+      "str_ins" => Deserialize::deserialize(deserializer).map(Operation::StrIns),
+      #[cfg(feature = "extensions")]
+      "str_del" => Deserialize::deserialize(deserializer).map(Operation::StrDel),
+      #[cfg(feature = "extensions")]
+      "add_to_set" => Deserialize::deserialize(deserializer).map(Operation::AddToSet),
+      #[cfg(feature = "extensions")]
+      "pull" => Deserialize::deserialize(deserializer).map(Operation::Pull),
+      _ => match parse_custom(&name, Map::deserialize(deserializer)?) {
+        Ok(Some(op)) => Ok(Operation::Custom(op)),
+        Ok(None) => Err(A::Error::unknown_variant(&name, STANDARD)),
+        Err(error) => Err(A::Error::custom(error)),
+      },
+    }
+  }
+}
+
+/// The members of an operation object following `op`, preceded by the
+/// members buffered while looking for it.
+struct Members<'de, A> {
+  buffered: IntoIter<(String, Value)>,
+  value: Option<Value>,
+  map: A,
+  marker: PhantomData<&'de ()>,
+}
+
+impl<'de, A> MapAccess<'de> for Members<'de, A>
+where
+  A: MapAccess<'de>,
+{
+  type Error = A::Error;
+
+  fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+  where
+    K: DeserializeSeed<'de>,
+  {
+    match self.buffered.next() {
+      Some((key, value)) => {
+        self.value = Some(value);
+        seed.deserialize(key.into_deserializer()).map(Some)
+      }
+      None => match self.map.next_key::<String>()? {
+        Some(key) if key == "op" => Err(A::Error::duplicate_field("op")),
+        Some(key) => seed.deserialize(key.into_deserializer()).map(Some),
+        None => Ok(None),
+      },
+    }
+  }
+
+  fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+  where
+    V: DeserializeSeed<'de>,
+  {
+    match self.value.take() {
+      Some(value) => seed.deserialize(value).map_err(A::Error::custom),
+      None => self.map.next_value_seed(seed),
+    }
+  }
 }
 
 impl Operation {
-  pub fn apply(self, value: &mut Value) -> Result<()> {
+  /// Returns the target location of the operation.
+  pub fn path(&self) -> &str {
+    match self {
+      Self::Add(op) => &op.path,
+      Self::Remove(op) => &op.path,
+      Self::Replace(op) => &op.path,
+      Self::Move(op) => &op.path,
+      Self::Copy(op) => &op.path,
+      Self::Test(op) => &op.path,
+      Self::Inc(op) | Self::Dec(op) | Self::Mul(op) | Self::Min(op) | Self::Max(op) => &op.path,
+      Self::StrIns(op) => &op.path,
+      Self::StrDel(op) => &op.path,
+      Self::AddToSet(op) => &op.path,
+      Self::Pull(op) => &op.path,
+      Self::Custom(op) => op.path(),
+    }
+  }
+
+  /// Returns the source location of a `move` or `copy` operation.
+  pub fn from(&self) -> Option<&str> {
+    match self {
+      Self::Move(op) => Some(&op.from),
+      Self::Copy(op) => Some(&op.from),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn paths_mut(&mut self) -> (&mut String, Option<&mut String>) {
+    match self {
+      Self::Add(op) => (&mut op.path, None),
+      Self::Remove(op) => (&mut op.path, None),
+      Self::Replace(op) => (&mut op.path, None),
+      Self::Move(op) => (&mut op.path, Some(&mut op.from)),
+      Self::Copy(op) => (&mut op.path, Some(&mut op.from)),
+      Self::Test(op) => (&mut op.path, None),
+      Self::Inc(op) | Self::Dec(op) | Self::Mul(op) | Self::Min(op) | Self::Max(op) => {
+        (&mut op.path, None)
+      }
+      Self::StrIns(op) => (&mut op.path, None),
+      Self::StrDel(op) => (&mut op.path, None),
+      Self::AddToSet(op) => (&mut op.path, None),
+      Self::Pull(op) => (&mut op.path, None),
+      Self::Custom(op) => (op.path_mut(), None),
+    }
+  }
+
+  pub fn apply<D: Document>(self, value: &mut D) -> Result<()> {
     match self {
       Operation::Add(op) => {
-        let _: Option<Value> = add(value, op)?;
+        let _: Option<D> = add(value, &op.path, D::from_value(op.value)?)?;
       }
       Operation::Remove(op) => {
-        let _: Value = remove(value, op)?;
+        let _: D = remove(value, &op.path)?;
       }
       Operation::Replace(op) => {
-        let _: Value = replace(value, op)?;
+        let _: D = replace(value, &op.path, D::from_value(op.value)?)?;
       }
       Operation::Move(op) => {
-        let _: Option<Value> = move_(value, op)?;
+        let _: Option<D> = move_(value, &op.from, &op.path)?;
       }
       Operation::Copy(op) => {
-        let _: Option<Value> = copy(value, op)?;
+        let _: Option<D> = copy(value, &op.from, &op.path)?;
       }
       Operation::Test(op) => {
-        let _: () = test(value, op)?;
+        let _: () = test(value, &op.path, &op)?;
+      }
+      operation => {
+        let _: () = operation.edit(pointer_mut(value, operation.path())?)?;
       }
     }
 
     Ok(())
   }
+
+  /// Applies an operation modifying the value at its target location, i.e. a
+  /// custom or extension operation, to `target`, the value at that location.
+  ///
+  /// `target` is left unchanged if this fails.
+  pub(crate) fn edit<D: Document>(&self, target: &mut D) -> Result<()> {
+    match self {
+      Self::Inc(op) => op.apply(target, Arithmetic::Inc),
+      Self::Dec(op) => op.apply(target, Arithmetic::Dec),
+      Self::Mul(op) => op.apply(target, Arithmetic::Mul),
+      Self::Min(op) => op.apply(target, Arithmetic::Min),
+      Self::Max(op) => op.apply(target, Arithmetic::Max),
+      Self::StrIns(op) => op.apply(target),
+      Self::StrDel(op) => op.apply(target),
+      Self::AddToSet(op) => op.apply(target),
+      Self::Pull(op) => op.apply(target),
+      Self::Custom(op) => op.apply(target),
+      _ => unreachable!("standard operations are not applied as edits"),
+    }
+  }
 }
 
-fn pointer(value: &Value, pointer: impl AsRef<str>) -> Result<&Value> {
-  value.pointer(pointer.as_ref()).ok_or(Error::InvalidPointer)
+/// Returns the JSON value of a target location holding neither an object nor
+/// an array.
+pub(crate) fn scalar<D: Document>(target: &D) -> Result<Value> {
+  match target.kind() {
+    NodeKind::Other => target.to_scalar().ok_or(Error::UnsupportedValue),
+    NodeKind::Object | NodeKind::Array => Err(Error::InvalidTarget),
+  }
 }
 
-fn pointer_mut(value: &mut Value, pointer: impl AsRef<str>) -> Result<&mut Value> {
-  value
-    .pointer_mut(pointer.as_ref())
-    .ok_or(Error::InvalidPointer)
+fn pointer<D: Document>(value: &D, pointer: impl AsRef<str>) -> Result<&D> {
+  let pointer: &str = pointer.as_ref();
+
+  if !pointer.is_empty() && !pointer.starts_with('/') {
+    return Err(Error::InvalidPointer);
+  }
+
+  tokens(pointer).try_fold(value, |value, token| match value.kind() {
+    NodeKind::Object => value.object_get(&token).ok_or(Error::InvalidPointer),
+    NodeKind::Array => value
+      .array_get(parse_idx(&token, value.array_len())?)
+      .ok_or(Error::InvalidPointer),
+    NodeKind::Other => Err(Error::InvalidPointer),
+  })
 }
 
-fn split_at(pointer: &str) -> Result<(&str, &str)> {
+fn pointer_mut<D: Document>(value: &mut D, pointer: impl AsRef<str>) -> Result<&mut D> {
+  let pointer: &str = pointer.as_ref();
+
+  if !pointer.is_empty() && !pointer.starts_with('/') {
+    return Err(Error::InvalidPointer);
+  }
+
+  tokens(pointer).try_fold(value, |value, token| match value.kind() {
+    NodeKind::Object => value.object_get_mut(&token).ok_or(Error::InvalidPointer),
+    NodeKind::Array => {
+      let index: usize = parse_idx(&token, value.array_len())?;
+      value.array_get_mut(index).ok_or(Error::InvalidPointer)
+    }
+    NodeKind::Other => Err(Error::InvalidPointer),
+  })
+}
+
+pub(crate) fn split_at(pointer: &str) -> Result<(&str, Cow<'_, str>)> {
   pointer
     .rfind('/')
     .ok_or(Error::InvalidPointer)
-    .map(|index| (&pointer[..index], &pointer[index + 1..]))
+    .map(|index| (&pointer[..index], unescape(&pointer[index + 1..])))
 }
 
-fn parse_idx(value: &str, limit: usize) -> Result<usize> {
+pub(crate) fn parse_idx(value: &str, limit: usize) -> Result<usize> {
   // * If the currently referenced value is a JSON array, the reference
   //   token MUST contain either:
 
@@ -82,13 +348,18 @@ fn parse_idx(value: &str, limit: usize) -> Result<usize> {
     return Err(Error::InvalidPointer);
   }
 
+  // `usize::from_str` also accepts a leading "+".
+  if !value.bytes().all(|byte| byte.is_ascii_digit()) {
+    return Err(Error::InvalidPointer);
+  }
+
   match value.parse() {
     Ok(index) if index < limit => Ok(index),
     Ok(_) | Err(_) => Err(Error::InvalidPointer),
   }
 }
 
-fn add(output: &mut Value, op: OpAdd) -> Result<Option<Value>> {
+pub(crate) fn add<D: Document>(output: &mut D, path: &str, value: D) -> Result<Option<D>> {
   // The "add" operation performs one of the following functions,
   // depending upon what the target location references:
   //
@@ -107,27 +378,32 @@ fn add(output: &mut Value, op: OpAdd) -> Result<Option<Value>> {
   // For example:
   //
   // { "op": "add", "path": "/a/b/c", "value": [ "foo", "bar" ] }
-  if op.path.is_empty() {
-    Ok(Some(mem::replace(output, op.value)))
+  add_value(output, path, value)
+}
+
+fn add_value<D: Document>(output: &mut D, path: &str, value: D) -> Result<Option<D>> {
+  if path.is_empty() {
+    Ok(Some(mem::replace(output, value)))
   } else {
-    let (ptr, key) = split_at(&op.path)?;
+    let (ptr, key) = split_at(path)?;
+    let inner: &mut D = pointer_mut(output, ptr)?;
 
-    match pointer_mut(output, ptr)? {
-      Value::Object(inner) => Ok(inner.insert(key.into(), op.value)),
-      Value::Array(inner) if key == "-" => {
-        inner.push(op.value);
+    match inner.kind() {
+      NodeKind::Object => Ok(inner.object_insert(key.into_owned(), value)),
+      NodeKind::Array if key == "-" => {
+        inner.array_insert(inner.array_len(), value);
         Ok(None)
       }
-      Value::Array(inner) => {
-        inner.insert(parse_idx(key, inner.len() + 1)?, op.value);
+      NodeKind::Array => {
+        inner.array_insert(parse_idx(&key, inner.array_len() + 1)?, value);
         Ok(None)
       }
-      _ => Err(Error::InvalidPointer),
+      NodeKind::Other => Err(Error::InvalidPointer),
     }
   }
 }
 
-fn remove(output: &mut Value, op: OpRemove) -> Result<Value> {
+pub(crate) fn remove<D: Document>(output: &mut D, path: &str) -> Result<D> {
   // The "remove" operation removes the value at the target location.
   //
   // The target location MUST exist for the operation to be successful.
@@ -138,16 +414,17 @@ fn remove(output: &mut Value, op: OpRemove) -> Result<Value> {
   //
   // If removing an element from an array, any elements above the
   // specified index are shifted one position to the left.
-  let (ptr, key) = split_at(&op.path)?;
+  let (ptr, key) = split_at(path)?;
+  let inner: &mut D = pointer_mut(output, ptr)?;
 
-  match pointer_mut(output, ptr)? {
-    Value::Object(inner) => inner.remove(key).ok_or(Error::InvalidPointer),
-    Value::Array(inner) => Ok(inner.remove(parse_idx(key, inner.len())?)),
-    _ => Err(Error::InvalidPointer),
+  match inner.kind() {
+    NodeKind::Object => inner.object_remove(&key).ok_or(Error::InvalidPointer),
+    NodeKind::Array => Ok(inner.array_remove(parse_idx(&key, inner.array_len())?)),
+    NodeKind::Other => Err(Error::InvalidPointer),
   }
 }
 
-fn replace(output: &mut Value, op: OpReplace) -> Result<Value> {
+pub(crate) fn replace<D: Document>(output: &mut D, path: &str, value: D) -> Result<D> {
   // The "replace" operation replaces the value at the target location
   // with a new value. The operation object MUST contain a "value" member
   // whose content specifies the replacement value.
@@ -161,10 +438,10 @@ fn replace(output: &mut Value, op: OpReplace) -> Result<Value> {
   // This operation is functionally identical to a "remove" operation for
   // a value, followed immediately by an "add" operation at the same
   // location with the replacement value.
-  pointer_mut(output, &op.path).map(|other| mem::replace(other, op.value))
+  pointer_mut(output, path).map(|other| mem::replace(other, value))
 }
 
-fn move_(output: &mut Value, op: OpMove) -> Result<Option<Value>> {
+pub(crate) fn move_<D: Document>(output: &mut D, from: &str, path: &str) -> Result<Option<D>> {
   // The "move" operation removes the value at a specified location and
   // adds it to the target location.
   //
@@ -184,22 +461,16 @@ fn move_(output: &mut Value, op: OpMove) -> Result<Option<Value>> {
   //
   // The "from" location MUST NOT be a proper prefix of the "path"
   // location; i.e., a location cannot be moved into one of its children.
-  if op.path.starts_with(&op.from) && op.path[op.from.len()..].starts_with('/') {
+  if path.starts_with(from) && path[from.len()..].starts_with('/') {
     return Err(Error::InvalidPointer);
   }
 
-  let value: Value = remove(output, OpRemove { path: op.from })?;
+  let value: D = remove(output, from)?;
 
-  add(
-    output,
-    OpAdd {
-      path: op.path,
-      value,
-    },
-  )
+  add_value(output, path, value)
 }
 
-fn copy(output: &mut Value, op: OpCopy) -> Result<Option<Value>> {
+pub(crate) fn copy<D: Document>(output: &mut D, from: &str, path: &str) -> Result<Option<D>> {
   // The "copy" operation copies the value at a specified location to the
   // target location.
   //
@@ -215,18 +486,12 @@ fn copy(output: &mut Value, op: OpCopy) -> Result<Option<Value>> {
   //
   // This operation is functionally identical to an "add" operation at the
   // target location using the value specified in the "from" member.
-  let value: Value = pointer(output, &op.from)?.clone();
-
-  add(
-    output,
-    OpAdd {
-      path: op.path,
-      value,
-    },
-  )
+  let value: D = pointer(output, from)?.clone();
+
+  add_value(output, path, value)
 }
 
-fn test(output: &Value, op: OpTest) -> Result<()> {
+pub(crate) fn test<D: Document>(output: &D, path: &str, op: &OpTest) -> Result<()> {
   // The "test" operation tests that a value at the target location is
   // equal to a specified value.
   //
@@ -268,9 +533,5 @@ fn test(output: &Value, op: OpTest) -> Result<()> {
   // For example:
   //
   // { "op": "test", "path": "/a/b/c", "value": "foo" }
-  if matches!(pointer(output, op.path), Ok(pointer) if *pointer == op.value) {
-    Ok(())
-  } else {
-    Err(Error::InvalidTest)
-  }
+  op.check(pointer(output, path).ok())
 }
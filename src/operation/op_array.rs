@@ -0,0 +1,77 @@
+use serde_json::Value;
+
+use crate::document::Document;
+use crate::document::NodeKind;
+use crate::error::Error;
+use crate::error::Result;
+
+/// An "add_to_set" operation.
+///
+/// Appends a value to the array at the target location unless the array
+/// already contains an equal value.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct OpAddToSet {
+  /// A string containing a JSON-Pointer value that references a location within
+  /// the target document (the "target location") where the operation is
+  /// performed.
+  pub path: String,
+  /// The value to be added.
+  pub value: Value,
+}
+
+/// A "pull" operation.
+///
+/// Removes every value equal to a specified value from the array at the
+/// target location.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct OpPull {
+  /// A string containing a JSON-Pointer value that references a location within
+  /// the target document (the "target location") where the operation is
+  /// performed.
+  pub path: String,
+  /// The value to be removed.
+  pub value: Value,
+}
+
+impl OpAddToSet {
+  pub(crate) fn apply<D: Document>(&self, target: &mut D) -> Result<()> {
+    if target.kind() != NodeKind::Array {
+      return Err(Error::InvalidTarget);
+    }
+
+    let len: usize = target.array_len();
+
+    if position(target, &self.value, 0).is_none() {
+      target.array_insert(len, D::from_value(self.value.clone())?);
+    }
+
+    Ok(())
+  }
+}
+
+impl OpPull {
+  pub(crate) fn apply<D: Document>(&self, target: &mut D) -> Result<()> {
+    if target.kind() != NodeKind::Array {
+      return Err(Error::InvalidTarget);
+    }
+
+    let mut index: usize = 0;
+
+    while let Some(found) = position(target, &self.value, index) {
+      let _: D = target.array_remove(found);
+      index = found;
+    }
+
+    Ok(())
+  }
+}
+
+/// Returns the index of the first item of the array `target` at or after
+/// `start` equal to `value`.
+fn position<D: Document>(target: &D, value: &Value, start: usize) -> Option<usize> {
+  (start..target.array_len()).find(|&index| {
+    target
+      .array_get(index)
+      .is_some_and(|item| item.eq_value(value))
+  })
+}
@@ -0,0 +1,255 @@
+use core::any::Any;
+#[cfg(feature = "std")]
+use core::any::TypeId;
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use serde::de::DeserializeOwned;
+use serde::ser::Error as _;
+use serde::ser::SerializeMap;
+use serde::Serialize;
+use serde::Serializer;
+use serde_json::Map;
+use serde_json::Value;
+#[cfg(feature = "std")]
+use std::collections::btree_map::Entry;
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::document::Document;
+use crate::error::Error;
+use crate::error::Result;
+
+/// The names of the operations defined by RFC 6902.
+///
+/// Unknown operations are reported against these names only, so the error
+/// does not depend on the enabled features.
+pub(crate) const STANDARD: &[&str] = &["add", "remove", "replace", "move", "copy", "test"];
+
+/// The names of the built-in operations, reserved whether or not the
+/// `extensions` feature is enabled.
+#[cfg(feature = "std")]
+pub(crate) const OPERATIONS: &[&str] = &[
+  "add",
+  "remove",
+  "replace",
+  "move",
+  "copy",
+  "test",
+  "inc",
+  "dec",
+  "mul",
+  "min",
+  "max",
+  "str_ins",
+  "str_del",
+  "add_to_set",
+  "pull",
+];
+
+/// The parsers of registered custom operations by name.
+#[cfg(feature = "std")]
+static REGISTRY: RwLock<BTreeMap<&'static str, (TypeId, Parser)>> = RwLock::new(BTreeMap::new());
+
+#[cfg(feature = "std")]
+type Parser = fn(Value) -> serde_json::Result<OpCustom>;
+
+/// A custom operation that can be registered with [`register`].
+///
+/// The operation is deserialized from the members of its operation object,
+/// excluding `op`, and applied to the value at its target location.
+pub trait Extension: Clone + Debug + Send + Sync + Serialize + DeserializeOwned + 'static {
+  /// The name of the operation given by the `op` member.
+  const NAME: &'static str;
+
+  /// Returns the target location of the operation.
+  fn path(&self) -> &str;
+
+  /// Returns the target location of the operation for modification.
+  fn path_mut(&mut self) -> &mut String;
+
+  /// Applies the operation to the value at the target location.
+  ///
+  /// Changes made to `target` before a failure are discarded.
+  fn apply(&self, target: &mut Value) -> Result<()>;
+}
+
+/// Registers the custom operation `T`, allowing it to be deserialized as part
+/// of a [`Patch`][crate::Patch].
+///
+/// Registering `T` again has no effect. Returns
+/// [`Error::DuplicateOperation`] if its name belongs to a built-in operation
+/// or to another registered type.
+#[cfg(feature = "std")]
+pub fn register<T: Extension>() -> Result<()> {
+  if OPERATIONS.contains(&T::NAME) {
+    return Err(Error::DuplicateOperation(T::NAME));
+  }
+
+  let mut registry = REGISTRY.write().unwrap_or_else(|error| error.into_inner());
+
+  match registry.entry(T::NAME) {
+    Entry::Vacant(entry) => {
+      entry.insert((TypeId::of::<T>(), parse::<T>));
+      Ok(())
+    }
+    Entry::Occupied(entry) if entry.get().0 == TypeId::of::<T>() => Ok(()),
+    Entry::Occupied(_) => Err(Error::DuplicateOperation(T::NAME)),
+  }
+}
+
+#[cfg(feature = "std")]
+fn parse<T: Extension>(value: Value) -> serde_json::Result<OpCustom> {
+  serde_json::from_value::<T>(value).map(OpCustom::new)
+}
+
+/// A custom operation registered with [`register`].
+pub struct OpCustom(Box<dyn Erased>);
+
+impl OpCustom {
+  /// Creates a new `OpCustom` from a custom operation.
+  pub fn new<T: Extension>(op: T) -> Self {
+    Self(Box::new(op))
+  }
+
+  /// Returns the name of the operation.
+  pub fn name(&self) -> &'static str {
+    self.0.name()
+  }
+
+  /// Returns the target location of the operation.
+  pub fn path(&self) -> &str {
+    self.0.path()
+  }
+
+  pub(crate) fn path_mut(&mut self) -> &mut String {
+    self.0.path_mut()
+  }
+
+  /// Returns the operation if it is of type `T`.
+  pub fn downcast_ref<T: Extension>(&self) -> Option<&T> {
+    self.0.as_any().downcast_ref()
+  }
+
+  /// Applies the operation to `target`, the value at the target location.
+  ///
+  /// `target` is left unchanged if this fails.
+  pub fn apply<D: Document>(&self, target: &mut D) -> Result<()> {
+    let mut value: Value = target.to_value().ok_or(Error::UnsupportedValue)?;
+
+    self.0.apply(&mut value)?;
+    *target = D::from_value(value)?;
+
+    Ok(())
+  }
+}
+
+impl Clone for OpCustom {
+  fn clone(&self) -> Self {
+    Self(self.0.clone_box())
+  }
+}
+
+impl Debug for OpCustom {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_tuple("OpCustom").field(&self.0).finish()
+  }
+}
+
+impl PartialEq for OpCustom {
+  fn eq(&self, other: &Self) -> bool {
+    self.name() == other.name() && self.0.to_value().ok() == other.0.to_value().ok()
+  }
+}
+
+impl Serialize for OpCustom {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    let members: Map<String, Value> = match self.0.to_value().map_err(S::Error::custom)? {
+      Value::Object(members) => members,
+      _ => {
+        return Err(S::Error::custom(
+          "expected custom operation to be an object",
+        ))
+      }
+    };
+
+    let mut map: S::SerializeMap = serializer.serialize_map(Some(members.len() + 1))?;
+
+    map.serialize_entry("op", self.name())?;
+
+    for (key, value) in members.iter().filter(|(key, _)| *key != "op") {
+      map.serialize_entry(key, value)?;
+    }
+
+    map.end()
+  }
+}
+
+/// Parses the members of the registered custom operation `name`, or returns
+/// `None` if no such operation is registered.
+#[cfg(feature = "std")]
+pub(crate) fn parse_custom(
+  name: &str,
+  members: Map<String, Value>,
+) -> serde_json::Result<Option<OpCustom>> {
+  let parser: Option<Parser> = REGISTRY
+    .read()
+    .unwrap_or_else(|error| error.into_inner())
+    .get(name)
+    .map(|(_, parser)| *parser);
+
+  parser
+    .map(|parser| parser(Value::Object(members)))
+    .transpose()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn parse_custom(_: &str, _: Map<String, Value>) -> serde_json::Result<Option<OpCustom>> {
+  Ok(None)
+}
+
+/// An object-safe form of [`Extension`].
+trait Erased: Debug + Send + Sync {
+  fn name(&self) -> &'static str;
+  fn path(&self) -> &str;
+  fn path_mut(&mut self) -> &mut String;
+  fn apply(&self, target: &mut Value) -> Result<()>;
+  fn to_value(&self) -> serde_json::Result<Value>;
+  fn clone_box(&self) -> Box<dyn Erased>;
+  fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Extension> Erased for T {
+  fn name(&self) -> &'static str {
+    T::NAME
+  }
+
+  fn path(&self) -> &str {
+    Extension::path(self)
+  }
+
+  fn path_mut(&mut self) -> &mut String {
+    Extension::path_mut(self)
+  }
+
+  fn apply(&self, target: &mut Value) -> Result<()> {
+    Extension::apply(self, target)
+  }
+
+  fn to_value(&self) -> serde_json::Result<Value> {
+    serde_json::to_value(self)
+  }
+
+  fn clone_box(&self) -> Box<dyn Erased> {
+    Box::new(self.clone())
+  }
+
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
+}
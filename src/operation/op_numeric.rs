@@ -0,0 +1,103 @@
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use serde_json::Number;
+use serde_json::Value;
+
+use crate::document::Document;
+use crate::error::Error;
+use crate::error::Result;
+use crate::operation::compare_numbers;
+use crate::operation::scalar;
+
+/// An arithmetic operation on the number at the target location: "inc",
+/// "dec", "mul", "min" or "max".
+///
+/// Integers stay integers unless the result is out of the range of both `i64`
+/// and `u64`, in which case it is computed as a float.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct OpNumeric {
+  /// A string containing a JSON-Pointer value that references a location within
+  /// the target document (the "target location") where the operation is
+  /// performed.
+  pub path: String,
+  /// The operand of the operation.
+  pub value: Number,
+}
+
+/// The kind of an arithmetic operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Arithmetic {
+  Inc,
+  Dec,
+  Mul,
+  Min,
+  Max,
+}
+
+impl OpNumeric {
+  pub(crate) fn apply<D: Document>(&self, target: &mut D, kind: Arithmetic) -> Result<()> {
+    let number: Number = match scalar(target)? {
+      Value::Number(number) => number,
+      _ => return Err(Error::InvalidTarget),
+    };
+
+    let number: Number = match kind {
+      Arithmetic::Inc => compute(&number, &self.value, i128::checked_add, |lhs, rhs| {
+        lhs + rhs
+      })?,
+      Arithmetic::Dec => compute(&number, &self.value, i128::checked_sub, |lhs, rhs| {
+        lhs - rhs
+      })?,
+      Arithmetic::Mul => compute(&number, &self.value, i128::checked_mul, |lhs, rhs| {
+        lhs * rhs
+      })?,
+      Arithmetic::Min => select(number, &self.value, Ordering::Greater),
+      Arithmetic::Max => select(number, &self.value, Ordering::Less),
+    };
+
+    *target = D::from_value(Value::Number(number))?;
+
+    Ok(())
+  }
+}
+
+fn compute(
+  lhs: &Number,
+  rhs: &Number,
+  integer: fn(i128, i128) -> Option<i128>,
+  float: fn(f64, f64) -> f64,
+) -> Result<Number> {
+  if let (Some(lhs), Some(rhs)) = (to_integer(lhs), to_integer(rhs)) {
+    if let Some(value) = integer(lhs, rhs) {
+      if let Ok(value) = i64::try_from(value) {
+        return Ok(value.into());
+      }
+
+      if let Ok(value) = u64::try_from(value) {
+        return Ok(value.into());
+      }
+    }
+  }
+
+  match (lhs.as_f64(), rhs.as_f64()) {
+    (Some(lhs), Some(rhs)) => Number::from_f64(float(lhs, rhs)).ok_or(Error::UnsupportedValue),
+    (_, _) => Err(Error::UnsupportedValue),
+  }
+}
+
+/// Returns the value of an integer within the range of `i64` or `u64`.
+fn to_integer(number: &Number) -> Option<i128> {
+  number
+    .as_i64()
+    .map(i128::from)
+    .or_else(|| number.as_u64().map(i128::from))
+}
+
+/// Returns `rhs` if `lhs` compares to it as `ordering`, otherwise `lhs`.
+fn select(lhs: Number, rhs: &Number, ordering: Ordering) -> Number {
+  if compare_numbers(&lhs, rhs) == Some(ordering) {
+    rhs.clone()
+  } else {
+    lhs
+  }
+}
@@ -0,0 +1,394 @@
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::marker::PhantomData;
+use serde::de::Error as _;
+use serde::de::IgnoredAny;
+use serde::de::MapAccess;
+use serde::de::Visitor;
+use serde::ser::SerializeMap;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use serde_json::value::RawValue;
+#[cfg(feature = "predicates")]
+use serde_json::Number;
+use serde_json::Value;
+use std::borrow::Cow;
+
+use crate::document::Document;
+use crate::error::Error;
+use crate::error::Result;
+use crate::operation::add;
+#[cfg(feature = "predicates")]
+use crate::operation::condition;
+use crate::operation::copy;
+use crate::operation::move_;
+use crate::operation::remove;
+use crate::operation::replace;
+use crate::operation::test;
+use crate::operation::Condition;
+use crate::operation::OpAdd;
+use crate::operation::OpCopy;
+use crate::operation::OpMove;
+use crate::operation::OpRemove;
+use crate::operation::OpReplace;
+use crate::operation::OpTest;
+use crate::operation::Operation;
+#[cfg(feature = "predicates")]
+use crate::operation::ValueType;
+use crate::pointer::Pointer;
+
+/// A borrowed JSON Patch operation.
+///
+/// Paths borrow from the input whenever they contain no escape sequences and
+/// values are kept as unparsed JSON text.
+///
+/// Only the operations defined by RFC 6902 are supported; extension and
+/// custom operations are rejected as unknown.
+#[derive(Clone, Debug)]
+pub enum OperationRef<'a> {
+  Add {
+    path: Cow<'a, str>,
+    value: &'a RawValue,
+  },
+  Remove {
+    path: Cow<'a, str>,
+  },
+  Replace {
+    path: Cow<'a, str>,
+    value: &'a RawValue,
+  },
+  Move {
+    from: Cow<'a, str>,
+    path: Cow<'a, str>,
+  },
+  Copy {
+    from: Cow<'a, str>,
+    path: Cow<'a, str>,
+  },
+  Test {
+    path: Cow<'a, str>,
+    /// `null` for a conditional test.
+    value: &'a RawValue,
+    not: bool,
+    condition: Option<Condition>,
+  },
+}
+
+impl OperationRef<'_> {
+  /// Returns the target location of the operation.
+  pub fn path(&self) -> &str {
+    match self {
+      Self::Add { path, .. } => path,
+      Self::Remove { path } => path,
+      Self::Replace { path, .. } => path,
+      Self::Move { path, .. } => path,
+      Self::Copy { path, .. } => path,
+      Self::Test { path, .. } => path,
+    }
+  }
+
+  /// Returns the source location of a `move` or `copy` operation.
+  pub fn from(&self) -> Option<&str> {
+    match self {
+      Self::Move { from, .. } => Some(from),
+      Self::Copy { from, .. } => Some(from),
+      _ => None,
+    }
+  }
+
+  /// Returns the unparsed value of an `add`, `replace` or `test` operation.
+  pub fn value(&self) -> Option<&RawValue> {
+    match self {
+      Self::Add { value, .. } => Some(value),
+      Self::Replace { value, .. } => Some(value),
+      Self::Test {
+        condition: Some(_), ..
+      } => None,
+      Self::Test { value, .. } => Some(value),
+      _ => None,
+    }
+  }
+
+  /// Applies the operation to `output`, parsing its value but not copying its
+  /// paths.
+  pub(crate) fn apply<D: Document>(&self, output: &mut D) -> Result<()> {
+    match self {
+      Self::Add { path, value } => {
+        let _: Option<D> = add(output, path, D::from_value(parse(value)?)?)?;
+      }
+      Self::Remove { path } => {
+        let _: D = remove(output, path)?;
+      }
+      Self::Replace { path, value } => {
+        let _: D = replace(output, path, D::from_value(parse(value)?)?)?;
+      }
+      Self::Move { from, path } => {
+        let _: Option<D> = move_(output, from, path)?;
+      }
+      Self::Copy { from, path } => {
+        let _: Option<D> = copy(output, from, path)?;
+      }
+      Self::Test {
+        path,
+        value,
+        not,
+        condition,
+      } => {
+        // The test is checked against the value at `path`, its own path is
+        // left empty.
+        let op: OpTest = OpTest {
+          path: String::new(),
+          value: parse(value)?,
+          not: *not,
+          condition: condition.clone(),
+        };
+
+        let _: () = test(output, path, &op)?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Converts the operation into an owned [`Operation`], parsing its value.
+  pub fn to_operation(&self) -> Result<Operation> {
+    match self {
+      Self::Add { path, value } => Ok(Operation::Add(OpAdd {
+        path: path.to_string(),
+        value: parse(value)?,
+      })),
+      Self::Remove { path } => Ok(Operation::Remove(OpRemove {
+        path: path.to_string(),
+      })),
+      Self::Replace { path, value } => Ok(Operation::Replace(OpReplace {
+        path: path.to_string(),
+        value: parse(value)?,
+      })),
+      Self::Move { from, path } => Ok(Operation::Move(OpMove {
+        path: path.to_string(),
+        from: from.to_string(),
+      })),
+      Self::Copy { from, path } => Ok(Operation::Copy(OpCopy {
+        path: path.to_string(),
+        from: from.to_string(),
+      })),
+      Self::Test {
+        path,
+        value,
+        not,
+        condition,
+      } => Ok(Operation::Test(OpTest {
+        path: path.to_string(),
+        value: parse(value)?,
+        not: *not,
+        condition: condition.clone(),
+      })),
+    }
+  }
+}
+
+fn parse(value: &RawValue) -> Result<Value> {
+  serde_json::from_str(value.get()).map_err(|error| Error::InvalidValue {
+    path: Pointer::root(),
+    error,
+  })
+}
+
+/// The RFC 6902 operations, see [`OperationRef`].
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Kind {
+  Add,
+  Remove,
+  Replace,
+  Move,
+  Copy,
+  Test,
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum Field {
+  Op,
+  Path,
+  From,
+  Value,
+  #[cfg(feature = "predicates")]
+  Not,
+  #[cfg(feature = "predicates")]
+  Type,
+  #[cfg(feature = "predicates")]
+  Exists,
+  #[cfg(feature = "predicates")]
+  Lt,
+  #[cfg(feature = "predicates")]
+  Gt,
+  #[serde(other)]
+  Other,
+}
+
+#[derive(Deserialize)]
+#[serde(transparent)]
+struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+
+impl<'de: 'a, 'a> Deserialize<'de> for OperationRef<'a> {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    deserializer.deserialize_map(OperationVisitor(PhantomData))
+  }
+}
+
+struct OperationVisitor<'a>(PhantomData<OperationRef<'a>>);
+
+impl<'de: 'a, 'a> Visitor<'de> for OperationVisitor<'a> {
+  type Value = OperationRef<'a>;
+
+  fn expecting(&self, f: &mut Formatter) -> FmtResult {
+    f.write_str("a JSON Patch operation")
+  }
+
+  fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+  where
+    A: MapAccess<'de>,
+  {
+    let mut kind: Option<Kind> = None;
+    let mut path: Option<Cow<'a, str>> = None;
+    let mut from: Option<Cow<'a, str>> = None;
+    let mut value: Option<&'a RawValue> = None;
+    #[cfg(feature = "predicates")]
+    let mut not: Option<bool> = None;
+    #[cfg(feature = "predicates")]
+    let mut type_: Option<ValueType> = None;
+    #[cfg(feature = "predicates")]
+    let mut exists: Option<bool> = None;
+    #[cfg(feature = "predicates")]
+    let mut lt: Option<Number> = None;
+    #[cfg(feature = "predicates")]
+    let mut gt: Option<Number> = None;
+
+    while let Some(field) = map.next_key()? {
+      match field {
+        Field::Op if kind.is_some() => return Err(A::Error::duplicate_field("op")),
+        Field::Op => kind = Some(map.next_value()?),
+        Field::Path if path.is_some() => return Err(A::Error::duplicate_field("path")),
+        Field::Path => path = Some(map.next_value::<Borrowed>()?.0),
+        Field::From if from.is_some() => return Err(A::Error::duplicate_field("from")),
+        Field::From => from = Some(map.next_value::<Borrowed>()?.0),
+        Field::Value if value.is_some() => return Err(A::Error::duplicate_field("value")),
+        Field::Value => value = Some(map.next_value()?),
+        #[cfg(feature = "predicates")]
+        Field::Not if not.is_some() => return Err(A::Error::duplicate_field("not")),
+        #[cfg(feature = "predicates")]
+        Field::Not => not = Some(map.next_value()?),
+        #[cfg(feature = "predicates")]
+        Field::Type if type_.is_some() => return Err(A::Error::duplicate_field("type")),
+        #[cfg(feature = "predicates")]
+        Field::Type => type_ = Some(map.next_value()?),
+        #[cfg(feature = "predicates")]
+        Field::Exists if exists.is_some() => return Err(A::Error::duplicate_field("exists")),
+        #[cfg(feature = "predicates")]
+        Field::Exists => exists = Some(map.next_value()?),
+        #[cfg(feature = "predicates")]
+        Field::Lt if lt.is_some() => return Err(A::Error::duplicate_field("lt")),
+        #[cfg(feature = "predicates")]
+        Field::Lt => lt = Some(map.next_value()?),
+        #[cfg(feature = "predicates")]
+        Field::Gt if gt.is_some() => return Err(A::Error::duplicate_field("gt")),
+        #[cfg(feature = "predicates")]
+        Field::Gt => gt = Some(map.next_value()?),
+        Field::Other => {
+          let _: IgnoredAny = map.next_value()?;
+        }
+      }
+    }
+
+    let kind: Kind = kind.ok_or_else(|| A::Error::missing_field("op"))?;
+    let path: Cow<'a, str> = path.ok_or_else(|| A::Error::missing_field("path"))?;
+
+    match kind {
+      Kind::Add => Ok(OperationRef::Add {
+        path,
+        value: value.ok_or_else(|| A::Error::missing_field("value"))?,
+      }),
+      Kind::Remove => Ok(OperationRef::Remove { path }),
+      Kind::Replace => Ok(OperationRef::Replace {
+        path,
+        value: value.ok_or_else(|| A::Error::missing_field("value"))?,
+      }),
+      Kind::Move => Ok(OperationRef::Move {
+        from: from.ok_or_else(|| A::Error::missing_field("from"))?,
+        path,
+      }),
+      Kind::Copy => Ok(OperationRef::Copy {
+        from: from.ok_or_else(|| A::Error::missing_field("from"))?,
+        path,
+      }),
+      #[cfg(not(feature = "predicates"))]
+      Kind::Test => Ok(OperationRef::Test {
+        path,
+        value: value.ok_or_else(|| A::Error::missing_field("value"))?,
+        not: false,
+        condition: None,
+      }),
+      #[cfg(feature = "predicates")]
+      Kind::Test => Ok(OperationRef::Test {
+        condition: condition(value.is_some(), type_, exists, lt, gt).map_err(|error| {
+          if value.is_none() {
+            A::Error::missing_field("value")
+          } else {
+            A::Error::custom(error)
+          }
+        })?,
+        path,
+        value: value.unwrap_or(RawValue::NULL),
+        not: not.unwrap_or_default(),
+      }),
+    }
+  }
+}
+
+impl Serialize for OperationRef<'_> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    let op: &str = match self {
+      Self::Add { .. } => "add",
+      Self::Remove { .. } => "remove",
+      Self::Replace { .. } => "replace",
+      Self::Move { .. } => "move",
+      Self::Copy { .. } => "copy",
+      Self::Test { .. } => "test",
+    };
+
+    let mut map = serializer.serialize_map(None)?;
+
+    map.serialize_entry("op", op)?;
+    map.serialize_entry("path", self.path())?;
+
+    if let Some(from) = self.from() {
+      map.serialize_entry("from", from)?;
+    }
+
+    if let Some(value) = self.value() {
+      map.serialize_entry("value", value)?;
+    }
+
+    if let Self::Test { not, condition, .. } = self {
+      if *not {
+        map.serialize_entry("not", not)?;
+      }
+
+      if let Some(condition) = condition {
+        let (name, value): (&str, Value) = condition.member();
+        map.serialize_entry(name, &value)?;
+      }
+    }
+
+    map.end()
+  }
+}
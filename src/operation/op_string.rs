@@ -0,0 +1,82 @@
+use serde_json::Value;
+
+use crate::document::Document;
+use crate::error::Error;
+use crate::error::Result;
+use crate::operation::scalar;
+
+/// A "str_ins" operation.
+///
+/// Inserts a string into the string at the target location.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct OpStrIns {
+  /// A string containing a JSON-Pointer value that references a location within
+  /// the target document (the "target location") where the operation is
+  /// performed.
+  pub path: String,
+  /// The character offset at which `value` is inserted, at most the length of
+  /// the string.
+  pub pos: usize,
+  /// The string to be inserted.
+  pub value: String,
+}
+
+/// A "str_del" operation.
+///
+/// Deletes a string from the string at the target location.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct OpStrDel {
+  /// A string containing a JSON-Pointer value that references a location within
+  /// the target document (the "target location") where the operation is
+  /// performed.
+  pub path: String,
+  /// The character offset of the deleted string.
+  pub pos: usize,
+  /// The string to be deleted, which MUST be equal to the characters at `pos`.
+  pub value: String,
+}
+
+impl OpStrIns {
+  pub(crate) fn apply<D: Document>(&self, target: &mut D) -> Result<()> {
+    let mut string: String = string(target)?;
+    let index: usize = offset(&string, self.pos)?;
+
+    string.insert_str(index, &self.value);
+    *target = D::from_value(Value::String(string))?;
+
+    Ok(())
+  }
+}
+
+impl OpStrDel {
+  pub(crate) fn apply<D: Document>(&self, target: &mut D) -> Result<()> {
+    let mut string: String = string(target)?;
+    let index: usize = offset(&string, self.pos)?;
+
+    if !string[index..].starts_with(&self.value) {
+      return Err(Error::InvalidTarget);
+    }
+
+    string.replace_range(index..index + self.value.len(), "");
+    *target = D::from_value(Value::String(string))?;
+
+    Ok(())
+  }
+}
+
+fn string<D: Document>(target: &D) -> Result<String> {
+  match scalar(target)? {
+    Value::String(string) => Ok(string),
+    _ => Err(Error::InvalidTarget),
+  }
+}
+
+/// Returns the byte index of the character offset `pos` within `string`.
+fn offset(string: &str, pos: usize) -> Result<usize> {
+  string
+    .char_indices()
+    .map(|(index, _)| index)
+    .chain(Some(string.len()))
+    .nth(pos)
+    .ok_or(Error::InvalidTarget)
+}
@@ -1,16 +1,276 @@
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use serde::Deserialize;
+use serde_json::Number;
 use serde_json::Value;
 
+use crate::document::Document;
+use crate::document::NodeKind;
+use crate::error::Error;
+use crate::error::Result;
+
 /// A JSON Patch "test" operation.
 ///
 /// Tests that a value at the target location is equal to a specified value.
 ///
+/// A test may instead check a [`Condition`] and any test may be negated. Only
+/// with the `predicates` feature are these read from the `type`, `exists`,
+/// `lt`, `gt` and `not` members of a patch, which are ignored otherwise.
+///
 /// [More Info](https://tools.ietf.org/html/rfc6902#section-4.6)
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "RawTest", into = "RawTest")]
 pub struct OpTest {
   /// A string containing a JSON-Pointer value that references a location within
   /// the target document (the "target location") where the operation is
   /// performed.
   pub path: String,
   /// The value to be compared to the target location's value.
+  ///
+  /// Unused if `condition` is set.
   pub value: Value,
+  /// If `true`, the test succeeds only if the comparison or condition fails.
+  pub not: bool,
+  /// A condition checked instead of equality with `value`.
+  pub condition: Option<Condition>,
+}
+
+/// A condition of an extended "test" operation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+  /// The target location holds a value of the given type.
+  Type(ValueType),
+  /// The target location exists.
+  Exists,
+  /// The target location does not exist.
+  Absent,
+  /// The target location holds a number less than the given number.
+  Lt(Number),
+  /// The target location holds a number greater than the given number.
+  Gt(Number),
+}
+
+/// The type of a JSON value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+  Null,
+  Boolean,
+  Number,
+  String,
+  Array,
+  Object,
+}
+
+impl OpTest {
+  /// Creates a new test for equality of the target location with `value`.
+  pub fn new(path: impl Into<String>, value: Value) -> Self {
+    Self {
+      path: path.into(),
+      value,
+      not: false,
+      condition: None,
+    }
+  }
+
+  /// Checks the test against `target`, the value at the target location or
+  /// `None` if it does not exist.
+  ///
+  /// A missing target location fails every test but an existence test.
+  pub(crate) fn check<D: Document>(&self, target: Option<&D>) -> Result<()> {
+    if self.matches(target)? {
+      Ok(())
+    } else {
+      Err(Error::InvalidTest)
+    }
+  }
+
+  /// A comparison with a missing target location or a value other than a
+  /// number fails even if the test is negated.
+  fn matches<D: Document>(&self, target: Option<&D>) -> Result<bool> {
+    let success: bool = match (&self.condition, target) {
+      (Some(Condition::Exists), target) => target.is_some(),
+      (Some(Condition::Absent), target) => target.is_none(),
+      (_, None) => return Ok(false),
+      (None, Some(target)) => target.eq_value(&self.value),
+      (Some(Condition::Type(kind)), Some(target)) => value_type(target)? == *kind,
+      (Some(Condition::Lt(number)), Some(target)) => match compare(target, number)? {
+        Some(ordering) => ordering == Ordering::Less,
+        None => return Ok(false),
+      },
+      (Some(Condition::Gt(number)), Some(target)) => match compare(target, number)? {
+        Some(ordering) => ordering == Ordering::Greater,
+        None => return Ok(false),
+      },
+    };
+
+    Ok(success != self.not)
+  }
+}
+
+fn value_type<D: Document>(target: &D) -> Result<ValueType> {
+  match target.kind() {
+    NodeKind::Object => Ok(ValueType::Object),
+    NodeKind::Array => Ok(ValueType::Array),
+    NodeKind::Other => match target.to_scalar().ok_or(Error::UnsupportedValue)? {
+      Value::Null => Ok(ValueType::Null),
+      Value::Bool(_) => Ok(ValueType::Boolean),
+      Value::Number(_) => Ok(ValueType::Number),
+      Value::String(_) => Ok(ValueType::String),
+      Value::Array(_) => Ok(ValueType::Array),
+      Value::Object(_) => Ok(ValueType::Object),
+    },
+  }
+}
+
+fn compare<D: Document>(target: &D, number: &Number) -> Result<Option<Ordering>> {
+  if target.kind() != NodeKind::Other {
+    return Ok(None);
+  }
+
+  match target.to_scalar().ok_or(Error::UnsupportedValue)? {
+    Value::Number(value) => Ok(compare_numbers(&value, number)),
+    _ => Ok(None),
+  }
+}
+
+pub(crate) fn compare_numbers(lhs: &Number, rhs: &Number) -> Option<Ordering> {
+  match (lhs.as_i64(), rhs.as_i64()) {
+    (Some(lhs), Some(rhs)) => Some(lhs.cmp(&rhs)),
+    (_, _) => match (lhs.as_u64(), rhs.as_u64()) {
+      (Some(lhs), Some(rhs)) => Some(lhs.cmp(&rhs)),
+      (_, _) => lhs.as_f64()?.partial_cmp(&rhs.as_f64()?),
+    },
+  }
+}
+
+/// The serialized representation of [`OpTest`].
+#[derive(Deserialize, Serialize)]
+struct RawTest {
+  path: String,
+  #[serde(
+    default,
+    deserialize_with = "present",
+    skip_serializing_if = "Option::is_none"
+  )]
+  value: Option<Value>,
+  #[serde(default, skip_serializing_if = "is_false")]
+  #[cfg_attr(not(feature = "predicates"), serde(skip_deserializing))]
+  not: bool,
+  #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+  #[cfg_attr(not(feature = "predicates"), serde(skip_deserializing))]
+  kind: Option<ValueType>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  #[cfg_attr(not(feature = "predicates"), serde(skip_deserializing))]
+  exists: Option<bool>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  #[cfg_attr(not(feature = "predicates"), serde(skip_deserializing))]
+  lt: Option<Number>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  #[cfg_attr(not(feature = "predicates"), serde(skip_deserializing))]
+  gt: Option<Number>,
+}
+
+impl Condition {
+  /// Returns the name and value of the operation member holding the condition.
+  pub(crate) fn member(&self) -> (&'static str, Value) {
+    match self {
+      Self::Type(kind) => ("type", serde_json::to_value(kind).unwrap_or_default()),
+      Self::Exists => ("exists", Value::Bool(true)),
+      Self::Absent => ("exists", Value::Bool(false)),
+      Self::Lt(number) => ("lt", Value::Number(number.clone())),
+      Self::Gt(number) => ("gt", Value::Number(number.clone())),
+    }
+  }
+}
+
+/// Returns the condition given by the members of a "test" operation, checking
+/// it against the presence of a `value` member.
+pub(crate) fn condition(
+  value: bool,
+  kind: Option<ValueType>,
+  exists: Option<bool>,
+  lt: Option<Number>,
+  gt: Option<Number>,
+) -> Result<Option<Condition>, &'static str> {
+  let conditions: [Option<Condition>; 4] = [
+    kind.map(Condition::Type),
+    exists.map(|exists| {
+      if exists {
+        Condition::Exists
+      } else {
+        Condition::Absent
+      }
+    }),
+    lt.map(Condition::Lt),
+    gt.map(Condition::Gt),
+  ];
+
+  let mut conditions = IntoIterator::into_iter(conditions).flatten();
+  let condition: Option<Condition> = conditions.next();
+
+  if conditions.next().is_some() {
+    return Err("expected at most one of `type`, `exists`, `lt` or `gt`");
+  }
+
+  match (value, condition) {
+    (true, Some(_)) => Err("unexpected field `value` in conditional test"),
+    (false, None) => Err("missing field `value`"),
+    (_, condition) => Ok(condition),
+  }
+}
+
+impl TryFrom<RawTest> for OpTest {
+  type Error = &'static str;
+
+  fn try_from(other: RawTest) -> Result<Self, Self::Error> {
+    Ok(Self {
+      condition: condition(
+        other.value.is_some(),
+        other.kind,
+        other.exists,
+        other.lt,
+        other.gt,
+      )?,
+      path: other.path,
+      value: other.value.unwrap_or_default(),
+      not: other.not,
+    })
+  }
+}
+
+impl From<OpTest> for RawTest {
+  fn from(other: OpTest) -> Self {
+    let mut this: Self = Self {
+      path: other.path,
+      value: None,
+      not: other.not,
+      kind: None,
+      exists: None,
+      lt: None,
+      gt: None,
+    };
+
+    match other.condition {
+      None => this.value = Some(other.value),
+      Some(Condition::Type(kind)) => this.kind = Some(kind),
+      Some(Condition::Exists) => this.exists = Some(true),
+      Some(Condition::Absent) => this.exists = Some(false),
+      Some(Condition::Lt(number)) => this.lt = Some(number),
+      Some(Condition::Gt(number)) => this.gt = Some(number),
+    }
+
+    this
+  }
+}
+
+fn present<'de, D>(deserializer: D) -> Result<Option<Value>, D::Error>
+where
+  D: serde::Deserializer<'de>,
+{
+  Value::deserialize(deserializer).map(Some)
+}
+
+fn is_false(value: &bool) -> bool {
+  !*value
 }
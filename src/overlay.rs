@@ -0,0 +1,351 @@
+use core::mem;
+use serde::Serialize;
+use serde::Serializer;
+use serde_json::Map;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::operation::parse_idx;
+use crate::operation::split_at;
+use crate::operation::Operation;
+use crate::patch::Patch;
+use crate::pointer::tokens;
+
+/// A patched view of a borrowed JSON document.
+///
+/// Changes are recorded on top of the base document instead of modifying a
+/// copy of it. Only the containers along the path of each operation are
+/// copied, one level at a time; all other values are shared with the base.
+#[derive(Clone, Debug)]
+pub struct Overlay<'a> {
+  root: Node<'a>,
+}
+
+#[derive(Clone, Debug)]
+enum Node<'a> {
+  /// An unmodified value of the base document.
+  Base(&'a Value),
+  /// A value introduced by the patch.
+  Owned(Value),
+  /// A modified object.
+  Object(BTreeMap<String, Node<'a>>),
+  /// A modified array.
+  Array(Vec<Node<'a>>),
+}
+
+/// A reference to a node or to a value within one.
+enum Target<'n, 'a> {
+  Base(&'a Value),
+  Owned(&'n Value),
+  Node(&'n Node<'a>),
+}
+
+impl<'a> Overlay<'a> {
+  /// Creates a new `Overlay` without changes to `base`.
+  pub fn new(base: &'a Value) -> Self {
+    Self {
+      root: Node::Base(base),
+    }
+  }
+
+  /// Records the operations of `patch` on top of the overlay.
+  ///
+  /// Operations applied before a failure are kept.
+  pub fn apply(&mut self, patch: Patch) -> Result<()> {
+    if patch.is_empty() {
+      return Ok(());
+    }
+
+    if !self.root.is_container() {
+      self.root = Node::Owned(Value::Object(Map::new()));
+    }
+
+    for operation in patch {
+      self.apply_operation(operation)?;
+    }
+
+    Ok(())
+  }
+
+  /// Returns the value referenced by `pointer`.
+  ///
+  /// Values unchanged by the overlay are borrowed; changed containers are
+  /// materialized.
+  pub fn get(&self, pointer: &str) -> Option<Cow<'_, Value>> {
+    match self.lookup(pointer).ok()? {
+      Target::Base(value) => Some(Cow::Borrowed(value)),
+      Target::Owned(value) => Some(Cow::Borrowed(value)),
+      Target::Node(node) => Some(node.to_value()),
+    }
+  }
+
+  /// Returns the patched document as an owned `Value`.
+  pub fn to_value(&self) -> Value {
+    self.root.to_value().into_owned()
+  }
+
+  /// Consumes the overlay and returns the patched document as an owned `Value`.
+  pub fn into_value(self) -> Value {
+    self.root.into_value()
+  }
+
+  fn apply_operation(&mut self, operation: Operation) -> Result<()> {
+    match operation {
+      Operation::Add(op) => self.add(&op.path, Node::Owned(op.value)),
+      Operation::Remove(op) => self.remove(&op.path).map(|_| ()),
+      Operation::Replace(op) => {
+        *self.lookup_mut(&op.path)? = Node::Owned(op.value);
+        Ok(())
+      }
+      Operation::Move(op) => {
+        if op.path.starts_with(&op.from) && op.path[op.from.len()..].starts_with('/') {
+          return Err(Error::InvalidPointer);
+        }
+
+        let node: Node<'a> = self.remove(&op.from)?;
+
+        self.add(&op.path, node)
+      }
+      Operation::Copy(op) => {
+        let node: Node<'a> = match self.lookup(&op.from)? {
+          Target::Base(value) => Node::Base(value),
+          Target::Owned(value) => Node::Owned(value.clone()),
+          Target::Node(node) => node.clone(),
+        };
+
+        self.add(&op.path, node)
+      }
+      Operation::Test(op) => {
+        let value: Option<Cow<'_, Value>> = match self.lookup(&op.path) {
+          Ok(Target::Base(value)) => Some(Cow::Borrowed(value)),
+          Ok(Target::Owned(value)) => Some(Cow::Borrowed(value)),
+          Ok(Target::Node(node)) => Some(node.to_value()),
+          Err(_) => None,
+        };
+
+        op.check(value.as_deref())
+      }
+      operation => {
+        let node: &mut Node<'a> = self.lookup_mut(operation.path())?;
+        let mut value: Value = node.to_value().into_owned();
+
+        operation.edit(&mut value)?;
+        *node = Node::Owned(value);
+
+        Ok(())
+      }
+    }
+  }
+
+  fn add(&mut self, path: &str, node: Node<'a>) -> Result<()> {
+    if path.is_empty() {
+      self.root = node;
+      return Ok(());
+    }
+
+    let (ptr, key) = split_at(path)?;
+    let parent: &mut Node<'a> = self.lookup_mut(ptr)?;
+
+    parent.promote();
+
+    match parent {
+      Node::Object(members) => {
+        members.insert(key.into_owned(), node);
+        Ok(())
+      }
+      Node::Array(items) if key == "-" => {
+        items.push(node);
+        Ok(())
+      }
+      Node::Array(items) => {
+        items.insert(parse_idx(&key, items.len() + 1)?, node);
+        Ok(())
+      }
+      _ => Err(Error::InvalidPointer),
+    }
+  }
+
+  fn remove(&mut self, path: &str) -> Result<Node<'a>> {
+    let (ptr, key) = split_at(path)?;
+    let parent: &mut Node<'a> = self.lookup_mut(ptr)?;
+
+    parent.promote();
+
+    match parent {
+      Node::Object(members) => members.remove(key.as_ref()).ok_or(Error::InvalidPointer),
+      Node::Array(items) => Ok(items.remove(parse_idx(&key, items.len())?)),
+      _ => Err(Error::InvalidPointer),
+    }
+  }
+
+  fn lookup(&self, pointer: &str) -> Result<Target<'_, 'a>> {
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+      return Err(Error::InvalidPointer);
+    }
+
+    tokens(pointer).try_fold(Target::Node(&self.root), |target, token| match target {
+      Target::Base(value) => child(value, &token).map(Target::Base),
+      Target::Owned(value) => child(value, &token).map(Target::Owned),
+      Target::Node(&Node::Base(value)) => child(value, &token).map(Target::Base),
+      Target::Node(Node::Owned(value)) => child(value, &token).map(Target::Owned),
+      Target::Node(Node::Object(members)) => members
+        .get(token.as_ref())
+        .map(Target::Node)
+        .ok_or(Error::InvalidPointer),
+      Target::Node(Node::Array(items)) => Ok(Target::Node(&items[parse_idx(&token, items.len())?])),
+    })
+  }
+
+  fn lookup_mut(&mut self, pointer: &str) -> Result<&mut Node<'a>> {
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+      return Err(Error::InvalidPointer);
+    }
+
+    tokens(pointer).try_fold(&mut self.root, |node, token| {
+      node.promote();
+
+      match node {
+        Node::Object(members) => members.get_mut(token.as_ref()).ok_or(Error::InvalidPointer),
+        Node::Array(items) => {
+          let index: usize = parse_idx(&token, items.len())?;
+          Ok(&mut items[index])
+        }
+        _ => Err(Error::InvalidPointer),
+      }
+    })
+  }
+}
+
+impl PartialEq<Value> for Overlay<'_> {
+  fn eq(&self, other: &Value) -> bool {
+    self.root.eq_value(other)
+  }
+}
+
+impl Serialize for Overlay<'_> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    self.root.serialize(serializer)
+  }
+}
+
+impl<'a> Node<'a> {
+  fn is_container(&self) -> bool {
+    match self {
+      Self::Base(value) => value.is_object() || value.is_array(),
+      Self::Owned(value) => value.is_object() || value.is_array(),
+      Self::Object(_) | Self::Array(_) => true,
+    }
+  }
+
+  /// Converts an unmodified container into a modifiable one, sharing its
+  /// children with the original value.
+  fn promote(&mut self) {
+    match self {
+      Self::Base(Value::Object(object)) => {
+        *self = Self::Object(
+          object
+            .iter()
+            .map(|(name, value)| (name.clone(), Self::Base(value)))
+            .collect(),
+        );
+      }
+      Self::Base(Value::Array(array)) => {
+        *self = Self::Array(array.iter().map(Self::Base).collect());
+      }
+      Self::Owned(Value::Object(object)) => {
+        *self = Self::Object(
+          mem::take(object)
+            .into_iter()
+            .map(|(name, value)| (name, Self::Owned(value)))
+            .collect(),
+        );
+      }
+      Self::Owned(Value::Array(array)) => {
+        *self = Self::Array(mem::take(array).into_iter().map(Self::Owned).collect());
+      }
+      _ => {}
+    }
+  }
+
+  fn eq_value(&self, other: &Value) -> bool {
+    match (self, other) {
+      (Self::Base(value), other) => *value == other,
+      (Self::Owned(value), other) => value == other,
+      (Self::Object(members), Value::Object(other)) => {
+        members.len() == other.len()
+          && members
+            .iter()
+            .all(|(name, node)| matches!(other.get(name), Some(other) if node.eq_value(other)))
+      }
+      (Self::Array(items), Value::Array(other)) => {
+        items.len() == other.len()
+          && items
+            .iter()
+            .zip(other.iter())
+            .all(|(node, other)| node.eq_value(other))
+      }
+      (_, _) => false,
+    }
+  }
+
+  fn to_value(&self) -> Cow<'_, Value> {
+    match self {
+      Self::Base(value) => Cow::Borrowed(value),
+      Self::Owned(value) => Cow::Borrowed(value),
+      Self::Object(members) => Cow::Owned(Value::Object(
+        members
+          .iter()
+          .map(|(name, node)| (name.clone(), node.to_value().into_owned()))
+          .collect(),
+      )),
+      Self::Array(items) => Cow::Owned(Value::Array(
+        items
+          .iter()
+          .map(|node| node.to_value().into_owned())
+          .collect(),
+      )),
+    }
+  }
+
+  fn into_value(self) -> Value {
+    match self {
+      Self::Base(value) => value.clone(),
+      Self::Owned(value) => value,
+      Self::Object(members) => Value::Object(
+        members
+          .into_iter()
+          .map(|(name, node)| (name, node.into_value()))
+          .collect(),
+      ),
+      Self::Array(items) => Value::Array(items.into_iter().map(Self::into_value).collect()),
+    }
+  }
+}
+
+impl Serialize for Node<'_> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    match self {
+      Self::Base(value) => value.serialize(serializer),
+      Self::Owned(value) => value.serialize(serializer),
+      Self::Object(members) => serializer.collect_map(members),
+      Self::Array(items) => serializer.collect_seq(items),
+    }
+  }
+}
+
+fn child<'v>(value: &'v Value, token: &str) -> Result<&'v Value> {
+  match value {
+    Value::Object(object) => object.get(token).ok_or(Error::InvalidPointer),
+    Value::Array(array) => Ok(&array[parse_idx(token, array.len())?]),
+    _ => Err(Error::InvalidPointer),
+  }
+}
@@ -0,0 +1,198 @@
+use rayon::iter::IntoParallelIterator;
+use rayon::iter::ParallelIterator;
+use serde_json::Map;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::operation::Operation;
+use crate::patch::prepare;
+use crate::patch::Patch;
+use crate::pointer::tokens;
+
+/// The operations of a patch affecting a single top-level member, along with
+/// their positions in the patch.
+type Partition = Vec<(usize, Operation)>;
+
+/// The error of a failed operation along with its position in the patch.
+type Failure = (usize, Error);
+
+/// The result of applying a partition to its top-level member.
+struct Outcome {
+  key: String,
+  /// The member before applying the partition.
+  original: Map<String, Value>,
+  /// The member after applying the partition.
+  target: Map<String, Value>,
+  operations: Partition,
+  /// The position of the last applied operation.
+  applied: Option<usize>,
+  error: Option<Failure>,
+}
+
+/// Applies `patch` to `output`, applying operations on distinct top-level
+/// members of an object concurrently.
+///
+/// Falls back to sequential application if any operation targets the whole
+/// document or moves or copies a value between top-level members.
+pub(crate) fn apply(patch: Patch, output: &mut Value) -> Result<()> {
+  if patch.is_empty() {
+    return Ok(());
+  }
+
+  prepare(output)?;
+
+  let object: &mut Map<String, Value> = match output {
+    Value::Object(object) => object,
+    _ => return patch.apply_mut(output),
+  };
+
+  let keys: Vec<String> = match patch.iter().map(member).collect() {
+    Some(keys) => keys,
+    None => return patch.apply_mut(output),
+  };
+
+  let mut partitions: BTreeMap<String, Partition> = BTreeMap::new();
+
+  for (index, (key, operation)) in keys.into_iter().zip(patch).enumerate() {
+    partitions.entry(key).or_default().push((index, operation));
+  }
+
+  if partitions.len() < 2 {
+    let patch: Patch = partitions
+      .into_values()
+      .flatten()
+      .map(|(_, operation)| operation)
+      .collect();
+
+    return patch.apply_mut(output);
+  }
+
+  let tasks: Vec<(String, Map<String, Value>, Partition)> = partitions
+    .into_iter()
+    .map(|(key, operations)| {
+      let mut target: Map<String, Value> = Map::new();
+
+      if let Some(value) = object.remove(&key) {
+        target.insert(key.clone(), value);
+      }
+
+      (key, target, operations)
+    })
+    .collect();
+
+  // Operations after a known failure are skipped, as they would not be
+  // applied sequentially.
+  let first: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+  let results: Vec<Outcome> = tasks
+    .into_par_iter()
+    .map(|(key, target, operations)| {
+      let original: Map<String, Value> = target.clone();
+      let mut target: Value = Value::Object(target);
+      let mut applied: Option<usize> = None;
+      let mut error: Option<Failure> = None;
+
+      for (index, operation) in operations.iter() {
+        if *index > first.load(Ordering::Relaxed) {
+          break;
+        }
+
+        if let Err(inner) = operation.clone().apply(&mut target) {
+          first.fetch_min(*index, Ordering::Relaxed);
+          error = Some((*index, inner));
+          break;
+        }
+
+        applied = Some(*index);
+      }
+
+      match target {
+        Value::Object(target) => Outcome {
+          key,
+          original,
+          target,
+          operations,
+          applied,
+          error,
+        },
+        _ => unreachable!(),
+      }
+    })
+    .collect();
+
+  let limit: usize = results
+    .iter()
+    .filter_map(|outcome| outcome.error.as_ref().map(|(index, _)| *index))
+    .min()
+    .unwrap_or(usize::MAX);
+
+  let mut failure: Option<Error> = None;
+
+  for outcome in results {
+    let Outcome {
+      key,
+      original,
+      mut target,
+      operations,
+      applied,
+      error,
+    } = outcome;
+
+    // Operations applied after the first failure are undone by applying the
+    // preceding operations of the partition to its original member again.
+    if applied.is_some_and(|index| index > limit) {
+      let mut value: Value = Value::Object(original);
+
+      // These operations succeeded before, so they cannot fail now.
+      let _: Result<()> = operations
+        .into_iter()
+        .take_while(|(index, _)| *index < limit)
+        .try_for_each(|(_, operation)| operation.apply(&mut value));
+
+      target = match value {
+        Value::Object(target) => target,
+        _ => unreachable!(),
+      };
+    }
+
+    if let Some(value) = target.remove(&key) {
+      object.insert(key, value);
+    }
+
+    if let Some((index, error)) = error {
+      if index == limit {
+        failure = Some(error);
+      }
+    }
+  }
+
+  match failure {
+    Some(error) => Err(error),
+    None => Ok(()),
+  }
+}
+
+/// Returns the top-level member affected by `operation`, or `None` if it
+/// cannot be applied independently of other members.
+fn member(operation: &Operation) -> Option<String> {
+  let key: String = token(operation.path())?;
+
+  match operation.from() {
+    Some(from) if token(from)? != key => None,
+    Some(_) | None => Some(key),
+  }
+}
+
+/// Returns the first reference token of `path`, or `None` if `path`
+/// references the whole document or is malformed.
+fn token(path: &str) -> Option<String> {
+  if path.starts_with('/') {
+    tokens(path).next().map(|token| token.into_owned())
+  } else {
+    None
+  }
+}
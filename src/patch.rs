@@ -1,8 +1,32 @@
+use core::iter::FromIterator;
+use core::slice::Iter;
+#[cfg(feature = "rayon")]
+use rayon::iter::IntoParallelRefMutIterator;
+#[cfg(feature = "rayon")]
+use rayon::iter::ParallelIterator;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::Value;
+use std::vec::IntoIter;
 
+use crate::compiled::CompiledPatch;
+use crate::document::Document;
+use crate::document::NodeKind;
+use crate::error::Error;
 use crate::error::Result;
+use crate::merge::merge_diff;
+use crate::merge::MergePatch;
 use crate::operation::Operation;
+use crate::overlay::Overlay;
+#[cfg(feature = "rayon")]
+use crate::parallel::apply as apply_par;
+use crate::pointer::is_prefix;
+use crate::pointer::is_valid;
+use crate::pointer::strip_prefix;
+use crate::pointer::Pointer;
+use crate::text::apply as apply_text;
 use crate::traits::CanPatch;
+use crate::typed::apply_typed;
 
 /// A sequence of JSON Patch operations.
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -19,22 +43,192 @@ impl Patch {
     self.0.is_empty()
   }
 
-  pub fn apply_ref(self, output: &Value) -> Result<Value> {
-    let mut value: Value = output.clone();
+  pub fn iter(&self) -> Iter<'_, Operation> {
+    self.0.iter()
+  }
+
+  /// Returns a new patch with every `path` and `from` nested under `prefix`.
+  ///
+  /// This mounts a patch written for a sub-document at a location within a
+  /// larger document. Fails with [`Error::InvalidPointer`] if any location is
+  /// not a valid JSON-Pointer.
+  pub fn prefixed(&self, prefix: &Pointer) -> Result<Self> {
+    let mut this: Self = self.clone();
+
+    for operation in this.0.iter_mut() {
+      let (path, from): (&mut String, Option<&mut String>) = operation.paths_mut();
+
+      prefix_mut(prefix, path)?;
+
+      if let Some(from) = from {
+        prefix_mut(prefix, from)?;
+      }
+    }
+
+    Ok(this)
+  }
+
+  /// Returns a new patch with `prefix` removed from every `path` and `from`.
+  ///
+  /// This is the inverse of [`Patch::prefixed`] and fails with
+  /// [`Error::InvalidPointer`] if any location is outside of `prefix`.
+  pub fn strip_prefix(&self, prefix: &Pointer) -> Result<Self> {
+    let mut this: Self = self.clone();
+
+    for operation in this.0.iter_mut() {
+      let (path, from): (&mut String, Option<&mut String>) = operation.paths_mut();
+
+      strip_prefix_mut(prefix, path)?;
+
+      if let Some(from) = from {
+        strip_prefix_mut(prefix, from)?;
+      }
+    }
+
+    Ok(this)
+  }
+
+  /// Returns a new patch containing only the operations matching `predicate`.
+  pub fn filter(&self, mut predicate: impl FnMut(&Operation) -> bool) -> Self {
+    self
+      .0
+      .iter()
+      .filter(|operation| predicate(operation))
+      .cloned()
+      .collect()
+  }
+
+  /// Splits the patch into the operations within `prefix` and all others.
+  ///
+  /// `test` operations targeting an ancestor of `prefix` guard both sides and
+  /// are included in each. Such a test sees the changes of both sides, so it
+  /// must precede every operation other than `test`.
+  ///
+  /// Fails with [`Error::InvalidPartition`] if any other operation spans both
+  /// sides, e.g. a `move` out of `prefix`, or if a `test` of an ancestor
+  /// follows a change to the document.
+  pub fn partition(&self, prefix: &Pointer) -> Result<(Self, Self)> {
+    let mut inner: Vec<Operation> = Vec::new();
+    let mut outer: Vec<Operation> = Vec::new();
+    let mut changed: bool = false;
+
+    for operation in self.0.iter() {
+      let path: Side = Side::new(prefix, operation.path());
+      let from: Side = operation
+        .from()
+        .map_or(path, |from| Side::new(prefix, from));
+
+      match (operation, path, from) {
+        (Operation::Test(_), Side::Both, Side::Both) if !changed => {
+          inner.push(operation.clone());
+          outer.push(operation.clone());
+        }
+        (_, Side::Inner, Side::Inner) => inner.push(operation.clone()),
+        (_, Side::Outer, Side::Outer) => outer.push(operation.clone()),
+        (_, _, _) => return Err(Error::InvalidPartition),
+      }
+
+      changed |= !matches!(operation, Operation::Test(_));
+    }
+
+    Ok((Self(inner), Self(outer)))
+  }
+
+  /// Splits the patch into the operations whose locations match `predicate`
+  /// and all others.
+  ///
+  /// `predicate` is called with the `path` and `from` of each operation.
+  /// Fails with [`Error::InvalidPartition`] if an operation has a `path` and
+  /// `from` on different sides.
+  pub fn partition_by(&self, mut predicate: impl FnMut(&str) -> bool) -> Result<(Self, Self)> {
+    let mut inner: Vec<Operation> = Vec::new();
+    let mut outer: Vec<Operation> = Vec::new();
+
+    for operation in self.0.iter() {
+      let path: bool = predicate(operation.path());
+
+      if operation.from().is_some_and(|from| predicate(from) != path) {
+        return Err(Error::InvalidPartition);
+      }
+
+      if path {
+        inner.push(operation.clone());
+      } else {
+        outer.push(operation.clone());
+      }
+    }
+
+    Ok((Self(inner), Self(outer)))
+  }
+
+  /// Converts the patch into an equivalent merge patch relative to `base`.
+  ///
+  /// Fails if the patch cannot be applied to `base` or if the result is not
+  /// representable as a merge patch.
+  pub fn to_merge_patch(&self, base: &Value) -> Result<MergePatch> {
+    merge_diff(base, &self.clone().apply_ref(base)?)
+  }
+
+  /// Parses and validates every pointer of the patch once, returning a
+  /// [`CompiledPatch`] that can be applied repeatedly.
+  pub fn compile(&self) -> Result<CompiledPatch> {
+    CompiledPatch::new(self)
+  }
+
+  /// Applies the patch to each document of `docs`, parsing its pointers once.
+  ///
+  /// Each document is left unchanged if the patch fails to apply to it.
+  pub fn apply_many<D: Document>(&self, docs: &mut [D]) -> Vec<Result<()>> {
+    match self.compile() {
+      Ok(patch) => patch.apply_many(docs),
+      Err(_) => docs.iter_mut().map(|doc| self.apply_atomic(doc)).collect(),
+    }
+  }
+
+  /// Applies the patch to each document of `docs` concurrently, parsing its
+  /// pointers once.
+  ///
+  /// Each document is left unchanged if the patch fails to apply to it.
+  #[cfg(feature = "rayon")]
+  pub fn apply_many_par<D: Document + Send>(&self, docs: &mut [D]) -> Vec<Result<()>> {
+    match self.compile() {
+      Ok(patch) => patch.apply_many_par(docs),
+      Err(_) => docs
+        .par_iter_mut()
+        .map(|doc| self.apply_atomic(doc))
+        .collect(),
+    }
+  }
+
+  fn apply_atomic<D: Document>(&self, output: &mut D) -> Result<()> {
+    *output = self.clone().apply_ref(output)?;
+    Ok(())
+  }
+
+  pub fn apply_ref<D: Document>(self, output: &D) -> Result<D> {
+    let mut value: D = output.clone();
 
     self.apply_mut(&mut value)?;
 
     Ok(value)
   }
 
-  pub fn apply_mut(self, output: &mut Value) -> Result<()> {
+  /// Applies the patch on top of the borrowed document `base` without copying
+  /// the parts of it left unchanged by the patch.
+  pub fn apply_overlay(self, base: &Value) -> Result<Overlay<'_>> {
+    let mut overlay: Overlay<'_> = Overlay::new(base);
+
+    overlay.apply(self)?;
+
+    Ok(overlay)
+  }
+
+  pub fn apply_mut<D: Document>(self, output: &mut D) -> Result<()> {
     if self.is_empty() {
       return Ok(());
     }
 
-    if !matches!(output, Value::Object(_) | Value::Array(_)) {
-      *output = Value::Object(Default::default());
-    }
+    prepare(output)?;
 
     for operation in self.0 {
       operation.apply(output)?;
@@ -43,18 +237,149 @@ impl Patch {
     Ok(())
   }
 
+  /// Applies the patch to `output`, applying operations on distinct top-level
+  /// members of the document concurrently.
+  ///
+  /// The result is the same as with [`apply_mut`][Self::apply_mut]. Patches
+  /// with operations on the whole document, or moving or copying values
+  /// between top-level members, are applied sequentially. If an operation
+  /// fails, its error is returned and only the operations preceding it are
+  /// applied, as with `apply_mut`; to do so, each top-level member is copied
+  /// before it is patched.
+  #[cfg(feature = "rayon")]
+  pub fn apply_par(self, output: &mut Value) -> Result<()> {
+    apply_par(self, output)
+  }
+
+  /// Applies the patch to a typed value via its JSON representation.
+  ///
+  /// `value` is left unchanged if the patch fails or the patched document
+  /// cannot be deserialized into `T`.
+  pub fn apply_to<T>(&self, value: &mut T) -> Result<()>
+  where
+    T: Serialize + DeserializeOwned,
+  {
+    apply_typed(value, |json| self.clone().apply_mut(json))
+  }
+
+  /// Applies the patch to the JSON document `text`.
+  ///
+  /// Only the regions of `text` changed by the patch are rewritten; untouched
+  /// regions keep their original bytes, whitespace and member order.
+  pub fn apply_text(self, text: &str) -> Result<String> {
+    apply_text(text, self.0)
+  }
+
   pub fn apply_fun(self, mut f: impl CanPatch) -> Result<()> {
+    self.apply_in_place(&mut f)
+  }
+
+  /// Applies the patch to a value implementing [`CanPatch`] without converting
+  /// it to a JSON document.
+  pub fn apply_in_place<T>(self, target: &mut T) -> Result<()>
+  where
+    T: CanPatch + ?Sized,
+  {
     for operation in self.0 {
       match operation {
-        Operation::Add(op) => f.patch_add(op)?,
-        Operation::Remove(op) => f.patch_remove(op)?,
-        Operation::Replace(op) => f.patch_replace(op)?,
-        Operation::Move(op) => f.patch_move(op)?,
-        Operation::Copy(op) => f.patch_copy(op)?,
-        Operation::Test(op) => f.patch_test(op)?,
+        Operation::Add(op) => target.patch_add(op)?,
+        Operation::Remove(op) => target.patch_remove(op)?,
+        Operation::Replace(op) => target.patch_replace(op)?,
+        Operation::Move(op) => target.patch_move(op)?,
+        Operation::Copy(op) => target.patch_copy(op)?,
+        Operation::Test(op) => target.patch_test(op)?,
+        Operation::Custom(op) => target.patch_custom(op)?,
+        operation => target.patch_extension(operation)?,
       }
     }
 
     Ok(())
   }
 }
+
+impl From<Vec<Operation>> for Patch {
+  fn from(other: Vec<Operation>) -> Self {
+    Self(other)
+  }
+}
+
+impl From<Patch> for Vec<Operation> {
+  fn from(other: Patch) -> Self {
+    other.0
+  }
+}
+
+impl FromIterator<Operation> for Patch {
+  fn from_iter<I>(iter: I) -> Self
+  where
+    I: IntoIterator<Item = Operation>,
+  {
+    Self(iter.into_iter().collect())
+  }
+}
+
+impl IntoIterator for Patch {
+  type Item = Operation;
+  type IntoIter = IntoIter<Operation>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.0.into_iter()
+  }
+}
+
+impl<'a> IntoIterator for &'a Patch {
+  type Item = &'a Operation;
+  type IntoIter = Iter<'a, Operation>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.0.iter()
+  }
+}
+
+pub(crate) fn prepare<D: Document>(output: &mut D) -> Result<()> {
+  if output.kind() == NodeKind::Other {
+    *output = D::from_value(Value::Object(Default::default()))?;
+  }
+
+  Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Side {
+  Inner,
+  Outer,
+  Both,
+}
+
+impl Side {
+  fn new(prefix: &Pointer, path: &str) -> Self {
+    if is_prefix(prefix, path) {
+      Self::Inner
+    } else if is_prefix(path, prefix) {
+      Self::Both
+    } else {
+      Self::Outer
+    }
+  }
+}
+
+fn prefix_mut(prefix: &Pointer, path: &mut String) -> Result<()> {
+  if !is_valid(path) {
+    return Err(Error::InvalidPointer);
+  }
+
+  path.insert_str(0, prefix.as_str());
+
+  Ok(())
+}
+
+fn strip_prefix_mut(prefix: &Pointer, path: &mut String) -> Result<()> {
+  let length: usize = path.len()
+    - strip_prefix(prefix, path)
+      .ok_or(Error::InvalidPointer)?
+      .len();
+
+  path.replace_range(..length, "");
+
+  Ok(())
+}
@@ -0,0 +1,62 @@
+use crate::document::Document;
+use crate::error::Result;
+use crate::operation::OperationRef;
+use crate::patch::prepare;
+use crate::patch::Patch;
+
+/// A borrowed sequence of JSON Patch operations.
+///
+/// Deserializing a `PatchRef` avoids copying paths and defers parsing values
+/// until the patch is applied or converted into a [`Patch`].
+///
+/// Only the operations defined by RFC 6902 are supported; extension and
+/// custom operations are rejected as unknown and require a [`Patch`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct PatchRef<'a>(#[serde(borrow)] Vec<OperationRef<'a>>);
+
+impl<'a> PatchRef<'a> {
+  pub fn len(&self) -> usize {
+    self.0.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &OperationRef<'a>> {
+    self.0.iter()
+  }
+
+  pub fn to_patch(&self) -> Result<Patch> {
+    self.0.iter().map(OperationRef::to_operation).collect()
+  }
+
+  pub fn apply_ref<D: Document>(&self, output: &D) -> Result<D> {
+    let mut value: D = output.clone();
+
+    self.apply_mut(&mut value)?;
+
+    Ok(value)
+  }
+
+  pub fn apply_mut<D: Document>(&self, output: &mut D) -> Result<()> {
+    if self.is_empty() {
+      return Ok(());
+    }
+
+    prepare(output)?;
+
+    for operation in self.0.iter() {
+      operation.apply(output)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl<'a> From<Vec<OperationRef<'a>>> for PatchRef<'a> {
+  fn from(other: Vec<OperationRef<'a>>) -> Self {
+    Self(other)
+  }
+}
@@ -0,0 +1,213 @@
+use core::borrow::Borrow;
+use core::convert::TryFrom;
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::ops::Deref;
+use core::str::FromStr;
+use std::borrow::Cow;
+
+use crate::error::Error;
+use crate::error::Result;
+
+/// A JavaScript Object Notation (JSON) Pointer.
+///
+/// [More Info](https://tools.ietf.org/html/rfc6901)
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Pointer(String);
+
+impl Pointer {
+  /// Creates a new `Pointer` referencing the whole document.
+  pub const fn root() -> Self {
+    Self(String::new())
+  }
+
+  /// Parses a `Pointer` from its string representation.
+  pub fn new(pointer: impl Into<String>) -> Result<Self> {
+    let pointer: String = pointer.into();
+
+    if is_valid(&pointer) {
+      Ok(Self(pointer))
+    } else {
+      Err(Error::InvalidPointer)
+    }
+  }
+
+  /// Creates a new `Pointer` from a sequence of unescaped reference tokens.
+  pub fn from_tokens<I, T>(tokens: I) -> Self
+  where
+    I: IntoIterator<Item = T>,
+    T: AsRef<str>,
+  {
+    let mut this: Self = Self::root();
+
+    for token in tokens {
+      this.push(token.as_ref());
+    }
+
+    this
+  }
+
+  /// Returns the string representation of the pointer.
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+
+  /// Consumes the pointer and returns the underlying `String`.
+  pub fn into_string(self) -> String {
+    self.0
+  }
+
+  /// Returns `true` if the pointer references the whole document.
+  pub fn is_root(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  /// Returns an iterator over the unescaped reference tokens of the pointer.
+  pub fn tokens(&self) -> impl Iterator<Item = Cow<'_, str>> {
+    tokens(&self.0)
+  }
+
+  /// Returns the parent of the pointer, or `None` for the root pointer.
+  pub fn parent(&self) -> Option<Self> {
+    self.0.rfind('/').map(|index| Self(self.0[..index].into()))
+  }
+
+  /// Returns the last unescaped reference token, or `None` for the root pointer.
+  pub fn last(&self) -> Option<Cow<'_, str>> {
+    self
+      .0
+      .rfind('/')
+      .map(|index| unescape(&self.0[index + 1..]))
+  }
+
+  /// Appends an unescaped reference token to the pointer.
+  pub fn push(&mut self, token: &str) {
+    self.0.push('/');
+    self.0.push_str(&escape(token));
+  }
+
+  /// Returns a new pointer with `other` appended to `self`.
+  pub fn join(&self, other: &Self) -> Self {
+    Self(format!("{}{}", self.0, other.0))
+  }
+
+  /// Returns `true` if `other` is equal to `self` or references a location
+  /// within it.
+  pub fn is_prefix_of(&self, other: &Self) -> bool {
+    is_prefix(&self.0, &other.0)
+  }
+
+  /// Returns the pointer relative to `prefix`, or `None` if `prefix` does not
+  /// reference an ancestor of `self`.
+  pub fn strip_prefix(&self, prefix: &Self) -> Option<Self> {
+    strip_prefix(&prefix.0, &self.0).map(|path| Self(path.into()))
+  }
+}
+
+impl Display for Pointer {
+  fn fmt(&self, f: &mut Formatter) -> FmtResult {
+    f.write_str(&self.0)
+  }
+}
+
+impl Deref for Pointer {
+  type Target = str;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl AsRef<str> for Pointer {
+  fn as_ref(&self) -> &str {
+    &self.0
+  }
+}
+
+impl Borrow<str> for Pointer {
+  fn borrow(&self) -> &str {
+    &self.0
+  }
+}
+
+impl FromStr for Pointer {
+  type Err = Error;
+
+  fn from_str(string: &str) -> Result<Self, Self::Err> {
+    Self::new(string)
+  }
+}
+
+impl TryFrom<&str> for Pointer {
+  type Error = Error;
+
+  fn try_from(string: &str) -> Result<Self, Self::Error> {
+    Self::new(string)
+  }
+}
+
+impl TryFrom<String> for Pointer {
+  type Error = Error;
+
+  fn try_from(string: String) -> Result<Self, Self::Error> {
+    Self::new(string)
+  }
+}
+
+impl From<Pointer> for String {
+  fn from(other: Pointer) -> Self {
+    other.0
+  }
+}
+
+pub(crate) fn is_valid(pointer: &str) -> bool {
+  if pointer.is_empty() {
+    return true;
+  }
+
+  if !pointer.starts_with('/') {
+    return false;
+  }
+
+  let mut chars = pointer.chars();
+
+  while let Some(char) = chars.next() {
+    if char == '~' && !matches!(chars.next(), Some('0') | Some('1')) {
+      return false;
+    }
+  }
+
+  true
+}
+
+pub(crate) fn is_prefix(prefix: &str, pointer: &str) -> bool {
+  strip_prefix(prefix, pointer).is_some()
+}
+
+pub(crate) fn strip_prefix<'a>(prefix: &str, pointer: &'a str) -> Option<&'a str> {
+  pointer
+    .strip_prefix(prefix)
+    .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+pub(crate) fn tokens(pointer: &str) -> impl Iterator<Item = Cow<'_, str>> {
+  pointer.split('/').skip(1).map(unescape)
+}
+
+pub(crate) fn escape(token: &str) -> Cow<'_, str> {
+  if token.contains(['~', '/']) {
+    Cow::Owned(token.replace('~', "~0").replace('/', "~1"))
+  } else {
+    Cow::Borrowed(token)
+  }
+}
+
+pub(crate) fn unescape(token: &str) -> Cow<'_, str> {
+  if token.contains('~') {
+    Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+  } else {
+    Cow::Borrowed(token)
+  }
+}
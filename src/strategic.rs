@@ -0,0 +1,248 @@
+use serde_json::Map;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::pointer::escape;
+use crate::pointer::Pointer;
+
+const DIRECTIVE: &str = "$patch";
+
+/// Merge keys used to match list elements in a strategic merge patch.
+///
+/// Lists are identified by a JSON-Pointer to their location in the document
+/// with list indices omitted, e.g. `/spec/containers/ports` for the `ports` of
+/// every element of `/spec/containers`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MergeKeys(BTreeMap<Pointer, String>);
+
+impl MergeKeys {
+  pub fn new() -> Self {
+    Self(BTreeMap::new())
+  }
+
+  /// Sets the field used to match elements of the list at `path`.
+  pub fn insert(&mut self, path: Pointer, key: impl Into<String>) {
+    self.0.insert(path, key.into());
+  }
+
+  /// Sets the field used to match elements of the list at `path`.
+  pub fn with(mut self, path: Pointer, key: impl Into<String>) -> Self {
+    self.insert(path, key);
+    self
+  }
+
+  /// Returns the field used to match elements of the list at `path`.
+  pub fn get(&self, path: &str) -> Option<&str> {
+    self.0.get(path).map(String::as_str)
+  }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Directive {
+  Merge,
+  Replace,
+  Delete,
+}
+
+impl Directive {
+  fn new(patch: &Map<String, Value>) -> Result<Self> {
+    match patch.get(DIRECTIVE) {
+      None => Ok(Self::Merge),
+      Some(Value::String(inner)) if inner == "merge" => Ok(Self::Merge),
+      Some(Value::String(inner)) if inner == "replace" => Ok(Self::Replace),
+      Some(Value::String(inner)) if inner == "delete" => Ok(Self::Delete),
+      Some(_) => Err(Error::InvalidMergePatch),
+    }
+  }
+}
+
+/// Applies a strategic merge patch to `value`.
+///
+/// This behaves like [`merge_mut`][crate::merge_mut] with the following
+/// extensions:
+///
+/// * Lists with a configured merge key are merged element-wise, matching
+///   elements by the value of their merge key. Unmatched elements are appended.
+///
+/// * An object containing `"$patch": "replace"` replaces the target object
+///   instead of being merged into it. A list element of the form
+///   `{"$patch": "replace"}` replaces the entire list with the remaining
+///   elements of the patch.
+///
+/// * An object containing `"$patch": "delete"` removes the target object. List
+///   elements are matched by merge key.
+///
+/// `value` is left unchanged if the patch is invalid.
+pub fn merge_strategic(value: &mut Value, patch: &Value, keys: &MergeKeys) -> Result<()> {
+  validate(patch, keys, &mut String::new())?;
+  strategic(value, patch, keys, &mut String::new())
+}
+
+/// Checks the directives and keyed list items of a patch without applying it.
+fn validate(patch: &Value, keys: &MergeKeys, path: &mut String) -> Result<()> {
+  match patch {
+    Value::Object(patch) => match Directive::new(patch)? {
+      Directive::Merge => {
+        for (name, other) in patch {
+          if name == DIRECTIVE || other.is_null() || is_delete(other)? {
+            continue;
+          }
+
+          let length: usize = path.len();
+
+          path.push('/');
+          path.push_str(&escape(name));
+          validate(other, keys, path)?;
+          path.truncate(length);
+        }
+
+        Ok(())
+      }
+      Directive::Replace => Ok(()),
+      Directive::Delete => Err(Error::InvalidMergePatch),
+    },
+    Value::Array(patch) => match keys.get(path) {
+      Some(key) => {
+        for item in patch {
+          let object: &Map<String, Value> = item.as_object().ok_or(Error::InvalidMergePatch)?;
+
+          match (Directive::new(object)?, object.contains_key(key)) {
+            (Directive::Delete, true) => {}
+            (_, true) => validate(item, keys, path)?,
+            (Directive::Replace, false) => {}
+            (_, false) => return Err(Error::InvalidMergePatch),
+          }
+        }
+
+        Ok(())
+      }
+      None => Ok(()),
+    },
+    _ => Ok(()),
+  }
+}
+
+fn strategic(value: &mut Value, patch: &Value, keys: &MergeKeys, path: &mut String) -> Result<()> {
+  match patch {
+    Value::Object(patch) => match Directive::new(patch)? {
+      Directive::Merge => {
+        if !value.is_object() {
+          *value = Value::Object(Map::new());
+        }
+
+        if let Value::Object(value) = value {
+          for (name, other) in patch {
+            if name == DIRECTIVE {
+              continue;
+            }
+
+            let length: usize = path.len();
+
+            path.push('/');
+            path.push_str(&escape(name));
+
+            if other.is_null() || is_delete(other)? {
+              value.remove(name.as_str());
+            } else {
+              strategic(
+                value.entry(name.as_str()).or_insert(Value::Null),
+                other,
+                keys,
+                path,
+              )?;
+            }
+
+            path.truncate(length);
+          }
+        }
+
+        Ok(())
+      }
+      Directive::Replace => {
+        *value = Value::Object(without_directive(patch));
+        Ok(())
+      }
+      Directive::Delete => Err(Error::InvalidMergePatch),
+    },
+    Value::Array(patch) => match keys.get(path) {
+      Some(key) => merge_list(value, patch, key, keys, path),
+      None => {
+        *value = Value::Array(patch.clone());
+        Ok(())
+      }
+    },
+    _ => {
+      *value = patch.clone();
+      Ok(())
+    }
+  }
+}
+
+fn merge_list(
+  value: &mut Value,
+  patch: &[Value],
+  key: &str,
+  keys: &MergeKeys,
+  path: &mut String,
+) -> Result<()> {
+  let mut items: Vec<(&Value, Directive, &Value)> = Vec::with_capacity(patch.len());
+  let mut replace: bool = false;
+
+  for item in patch {
+    let object: &Map<String, Value> = item.as_object().ok_or(Error::InvalidMergePatch)?;
+    let directive: Directive = Directive::new(object)?;
+
+    match object.get(key) {
+      Some(id) => items.push((item, directive, id)),
+      None if directive == Directive::Replace => replace = true,
+      None => return Err(Error::InvalidMergePatch),
+    }
+  }
+
+  if replace || !value.is_array() {
+    *value = Value::Array(Vec::new());
+  }
+
+  let list: &mut Vec<Value> = match value {
+    Value::Array(list) => list,
+    _ => unreachable!(),
+  };
+
+  for (item, directive, id) in items {
+    let index: Option<usize> = list.iter().position(|item| item.get(key) == Some(id));
+
+    match (directive, index) {
+      (Directive::Delete, Some(index)) => {
+        list.remove(index);
+      }
+      (Directive::Delete, None) => {}
+      (_, Some(index)) => {
+        strategic(&mut list[index], item, keys, path)?;
+      }
+      (_, None) => {
+        let mut value: Value = Value::Null;
+        strategic(&mut value, item, keys, path)?;
+        list.push(value);
+      }
+    }
+  }
+
+  Ok(())
+}
+
+fn is_delete(patch: &Value) -> Result<bool> {
+  match patch {
+    Value::Object(patch) => Directive::new(patch).map(|directive| directive == Directive::Delete),
+    _ => Ok(false),
+  }
+}
+
+fn without_directive(patch: &Map<String, Value>) -> Map<String, Value> {
+  patch
+    .iter()
+    .filter(|(name, _)| *name != DIRECTIVE)
+    .map(|(name, value)| (name.clone(), value.clone()))
+    .collect()
+}
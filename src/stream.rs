@@ -0,0 +1,123 @@
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use serde::de::Error as _;
+use serde::de::SeqAccess;
+use serde::de::Visitor;
+use serde::Deserializer as _;
+use serde_json::de::IoRead;
+use serde_json::Deserializer;
+use std::io::Read;
+
+use crate::document::Document;
+use crate::error::Error;
+use crate::error::Result;
+use crate::operation::Operation;
+use crate::patch::prepare;
+
+/// Applies a JSON Patch read incrementally from an [`io::Read`][Read].
+///
+/// Each operation is applied as soon as it is parsed, so the patch is never
+/// materialized in memory as a whole.
+#[derive(Debug)]
+pub struct PatchReader<R> {
+  reader: R,
+  atomic: bool,
+}
+
+impl<R: Read> PatchReader<R> {
+  /// Creates a new `PatchReader` reading operations from `reader`.
+  pub fn new(reader: R) -> Self {
+    Self {
+      reader,
+      atomic: false,
+    }
+  }
+
+  /// If `true`, the document is restored to its original state when an
+  /// operation fails or the input is malformed.
+  ///
+  /// This requires a copy of the document to be held while applying.
+  pub fn atomic(mut self, atomic: bool) -> Self {
+    self.atomic = atomic;
+    self
+  }
+
+  /// Applies the operations to `output`, returning the number of operations
+  /// applied.
+  ///
+  /// Unless configured as [`atomic`][Self::atomic], operations applied before
+  /// a failure are kept.
+  pub fn apply_mut<D: Document>(self, output: &mut D) -> Result<usize> {
+    let backup: Option<D> = if self.atomic {
+      Some(output.clone())
+    } else {
+      None
+    };
+
+    let result: Result<usize> = apply(self.reader, output);
+
+    if let (Err(_), Some(backup)) = (&result, backup) {
+      *output = backup;
+    }
+
+    result
+  }
+}
+
+fn apply<R: Read, D: Document>(reader: R, output: &mut D) -> Result<usize> {
+  let mut deserializer: Deserializer<IoRead<R>> = Deserializer::from_reader(reader);
+
+  let mut visitor: ApplyVisitor<'_, D> = ApplyVisitor {
+    output,
+    count: 0,
+    error: None,
+  };
+
+  let result: Result<(), serde_json::Error> = deserializer
+    .deserialize_seq(&mut visitor)
+    .and_then(|()| deserializer.end());
+
+  match (result, visitor.error) {
+    (Ok(()), _) => Ok(visitor.count),
+    (Err(_), Some(error)) => Err(error),
+    (Err(error), None) if error.is_io() => Err(Error::Io(error.into())),
+    (Err(error), None) => Err(Error::InvalidPatch(error)),
+  }
+}
+
+struct ApplyVisitor<'a, D> {
+  output: &'a mut D,
+  count: usize,
+  error: Option<Error>,
+}
+
+impl<'de, D: Document> Visitor<'de> for &mut ApplyVisitor<'_, D> {
+  type Value = ();
+
+  fn expecting(&self, f: &mut Formatter) -> FmtResult {
+    f.write_str("a sequence of JSON Patch operations")
+  }
+
+  fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+  where
+    A: SeqAccess<'de>,
+  {
+    while let Some(operation) = seq.next_element::<Operation>()? {
+      let result: Result<()> = if self.count == 0 {
+        prepare(self.output).and_then(|()| operation.apply(self.output))
+      } else {
+        operation.apply(self.output)
+      };
+
+      if let Err(error) = result {
+        let message: String = error.to_string();
+        self.error = Some(error);
+        return Err(A::Error::custom(message));
+      }
+
+      self.count += 1;
+    }
+
+    Ok(())
+  }
+}
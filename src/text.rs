@@ -0,0 +1,608 @@
+use serde::de::IgnoredAny;
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::operation::parse_idx;
+use crate::operation::split_at;
+use crate::operation::Operation;
+use crate::pointer::is_prefix;
+use crate::pointer::tokens;
+use crate::pointer::Pointer;
+
+/// A JSON document along with the location of its values in the source text.
+struct Source {
+  text: String,
+  root: Node,
+}
+
+/// A JSON value within a document.
+///
+/// Offsets are relative to the start of the enclosing container, so an edit
+/// only shifts the following siblings of the containers around it.
+struct Node {
+  offset: usize,
+  len: usize,
+  data: Data,
+}
+
+enum Data {
+  Object(Vec<Member>),
+  Array(Vec<Node>),
+  Scalar,
+}
+
+/// An object member; `offset` is the start of its key.
+struct Member {
+  key: String,
+  offset: usize,
+  value: Node,
+}
+
+enum Entry {
+  Member(Member),
+  Item(Node),
+}
+
+/// Applies `operations` to the JSON document `text`.
+///
+/// Only the spans of `text` affected by the operations are rewritten; all
+/// other bytes, including whitespace and member order, are preserved.
+pub(crate) fn apply(text: &str, operations: Vec<Operation>) -> Result<String> {
+  let mut source: Source = Source::new(text)?;
+
+  // Like `apply_mut`, a patch replaces a scalar document with an empty object
+  // before it is applied.
+  if !operations.is_empty() {
+    if let Data::Scalar = source.root.data {
+      source.replace(&[], "{}")?;
+    }
+  }
+
+  for operation in operations {
+    source.apply(operation)?;
+  }
+
+  Ok(source.text)
+}
+
+impl Source {
+  fn new(text: &str) -> Result<Self> {
+    // Validate the document up front so the parser below can assume
+    // well-formed input.
+    let _: IgnoredAny = from_str(text)?;
+
+    Ok(Self {
+      text: text.to_owned(),
+      root: parse(text, 0)?,
+    })
+  }
+
+  fn apply(&mut self, operation: Operation) -> Result<()> {
+    match operation {
+      Operation::Add(op) => self.add(&op.path, &to_string(&op.value)?),
+      Operation::Remove(op) => self.remove(&op.path),
+      Operation::Replace(op) => {
+        let indices: Vec<usize> = self.locate(&op.path)?;
+        self.replace(&indices, &to_string(&op.value)?)
+      }
+      Operation::Move(op) => {
+        if op.from != op.path && is_prefix(&op.from, &op.path) {
+          return Err(Error::InvalidPointer);
+        }
+
+        let value: String = self.value(&self.locate(&op.from)?).to_owned();
+
+        self.remove(&op.from)?;
+        self.add(&op.path, &value)
+      }
+      Operation::Copy(op) => {
+        let value: String = self.value(&self.locate(&op.from)?).to_owned();
+        self.add(&op.path, &value)
+      }
+      Operation::Test(op) => {
+        let value: Option<Value> = match self.locate(&op.path) {
+          Ok(indices) => Some(from_str(self.value(&indices))?),
+          Err(_) => None,
+        };
+
+        op.check(value.as_ref())
+      }
+      operation => {
+        let indices: Vec<usize> = self.locate(operation.path())?;
+        let mut value: Value = from_str(self.value(&indices))?;
+
+        operation.edit(&mut value)?;
+
+        self.replace(&indices, &to_string(&value)?)
+      }
+    }
+  }
+
+  fn add(&mut self, path: &str, value: &str) -> Result<()> {
+    if path.is_empty() {
+      return self.replace(&[], value);
+    }
+
+    let (ptr, key) = split_at(path)?;
+    let mut indices: Vec<usize> = self.locate(ptr)?;
+    let (parent, start): (&Node, usize) = self.node(&indices);
+    let interior: (usize, usize) = (start + 1, start + parent.len - 1);
+
+    match parent.data {
+      Data::Object(ref members) => {
+        if let Some(index) = members.iter().rposition(|member| member.key == key) {
+          indices.push(index);
+          return self.replace(&indices, value);
+        }
+
+        let (position, end, prefix): (usize, usize, String) = match members.last() {
+          Some(last) => (
+            start + last.value.end(),
+            start + last.value.end(),
+            format!(",{}", whitespace(&self.text, start + last.offset)),
+          ),
+          None => (interior.0, interior.1, String::new()),
+        };
+
+        let name: String = to_string(&key)?;
+        let offset: usize = position - start + prefix.len();
+
+        let member: Member = Member {
+          key: key.into_owned(),
+          offset,
+          value: parse(value, offset + name.len() + 2)?,
+        };
+
+        let entry: String = format!("{}{}: {}", prefix, name, value);
+        let index: usize = members.len();
+
+        self.insert(
+          &indices,
+          index,
+          position,
+          end,
+          &entry,
+          Entry::Member(member),
+        );
+      }
+      Data::Array(ref items) => {
+        let index: usize = if key == "-" {
+          items.len()
+        } else {
+          parse_idx(&key, items.len() + 1)?
+        };
+
+        let (position, end, entry, offset): (usize, usize, String, usize) =
+          if let Some(item) = items.get(index) {
+            let separator: &str = match index {
+              0 => whitespace(&self.text, start + items.get(1).unwrap_or(item).offset),
+              _ => whitespace(&self.text, start + item.offset),
+            };
+
+            let entry: String = format!("{},{}", value, separator);
+            (start + item.offset, start + item.offset, entry, item.offset)
+          } else if let Some(last) = items.last() {
+            let prefix: String = format!(",{}", whitespace(&self.text, start + last.offset));
+            let offset: usize = last.end() + prefix.len();
+            let entry: String = format!("{}{}", prefix, value);
+            (start + last.end(), start + last.end(), entry, offset)
+          } else {
+            (interior.0, interior.1, value.to_owned(), 1)
+          };
+
+        let item: Node = parse(value, offset)?;
+
+        self.insert(&indices, index, position, end, &entry, Entry::Item(item));
+      }
+      Data::Scalar => return Err(Error::InvalidPointer),
+    }
+
+    Ok(())
+  }
+
+  fn remove(&mut self, path: &str) -> Result<()> {
+    let (ptr, key) = split_at(path)?;
+    let indices: Vec<usize> = self.locate(ptr)?;
+
+    match self.node(&indices).0.data {
+      Data::Object(ref members) => {
+        // Every occurrence of a duplicate key is removed, as the member would
+        // otherwise reappear with the value of an earlier occurrence.
+        let matches: Vec<usize> = members
+          .iter()
+          .enumerate()
+          .filter(|(_, member)| member.key == key)
+          .map(|(index, _)| index)
+          .collect();
+
+        if matches.is_empty() {
+          return Err(Error::InvalidPointer);
+        }
+
+        for index in matches.into_iter().rev() {
+          self.remove_child(&indices, index);
+        }
+      }
+      Data::Array(ref items) => {
+        let index: usize = parse_idx(&key, items.len())?;
+        self.remove_child(&indices, index);
+      }
+      Data::Scalar => return Err(Error::InvalidPointer),
+    }
+
+    Ok(())
+  }
+
+  /// Replaces the node at `indices` with `value`.
+  fn replace(&mut self, indices: &[usize], value: &str) -> Result<()> {
+    let (node, start): (&Node, usize) = self.node(indices);
+    let end: usize = start + node.len;
+    let node: Node = parse(value, node.offset)?;
+
+    match indices.split_last() {
+      Some((&index, parent)) => {
+        *self.node_mut(indices) = node;
+        self.splice(parent, start, end, value, index + 1);
+      }
+      None => {
+        self.text.replace_range(start..end, value);
+        self.root = node;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Inserts `entry` as child `index` of the container at `indices`, replacing
+  /// the bytes `start..end` with `text`.
+  fn insert(
+    &mut self,
+    indices: &[usize],
+    index: usize,
+    start: usize,
+    end: usize,
+    text: &str,
+    entry: Entry,
+  ) {
+    match (&mut self.node_mut(indices).data, entry) {
+      (Data::Object(members), Entry::Member(member)) => members.insert(index, member),
+      (Data::Array(items), Entry::Item(item)) => items.insert(index, item),
+      _ => unreachable!(),
+    }
+
+    self.splice(indices, start, end, text, index + 1);
+  }
+
+  /// Removes child `index` of the container at `indices` along with its
+  /// separator.
+  fn remove_child(&mut self, indices: &[usize], index: usize) {
+    let (parent, start): (&Node, usize) = self.node(indices);
+
+    let spans: Vec<(usize, usize)> = match parent.data {
+      Data::Object(ref members) => members
+        .iter()
+        .map(|member| (member.offset, member.value.end()))
+        .collect(),
+      Data::Array(ref items) => items.iter().map(|item| (item.offset, item.end())).collect(),
+      Data::Scalar => unreachable!(),
+    };
+
+    let (from, to): (usize, usize) = if spans.len() == 1 {
+      (1, parent.len - 1)
+    } else if index + 1 < spans.len() {
+      (spans[index].0, spans[index + 1].0)
+    } else {
+      (spans[index - 1].1, spans[index].1)
+    };
+
+    match self.node_mut(indices).data {
+      Data::Object(ref mut members) => {
+        let _: Member = members.remove(index);
+      }
+      Data::Array(ref mut items) => {
+        let _: Node = items.remove(index);
+      }
+      Data::Scalar => unreachable!(),
+    }
+
+    self.splice(indices, start + from, start + to, "", index);
+  }
+
+  /// Replaces the bytes `start..end`, located within the container at
+  /// `indices`, with `value` and shifts the children of the container from
+  /// `from` onwards.
+  fn splice(&mut self, indices: &[usize], start: usize, end: usize, value: &str, from: usize) {
+    let removed: usize = end - start;
+    let inserted: usize = value.len();
+
+    self.text.replace_range(start..end, value);
+
+    let mut node: &mut Node = &mut self.root;
+
+    for &index in indices {
+      node.shift(index + 1, removed, inserted);
+      node = node.child_mut(index);
+    }
+
+    node.shift(from, removed, inserted);
+  }
+
+  /// Returns the child indices leading to the node at `path`.
+  fn locate(&self, path: &str) -> Result<Vec<usize>> {
+    if !path.is_empty() && !path.starts_with('/') {
+      return Err(Error::InvalidPointer);
+    }
+
+    let mut indices: Vec<usize> = Vec::new();
+    let mut node: &Node = &self.root;
+
+    for token in tokens(path) {
+      let index: usize = node.find(&token)?;
+
+      indices.push(index);
+      node = node.child(index);
+    }
+
+    Ok(indices)
+  }
+
+  /// Returns the node at `indices` along with its position in the text.
+  fn node(&self, indices: &[usize]) -> (&Node, usize) {
+    indices
+      .iter()
+      .fold((&self.root, self.root.offset), |(node, start), &index| {
+        let child: &Node = node.child(index);
+        (child, start + child.offset)
+      })
+  }
+
+  fn node_mut(&mut self, indices: &[usize]) -> &mut Node {
+    indices
+      .iter()
+      .fold(&mut self.root, |node, &index| node.child_mut(index))
+  }
+
+  /// Returns the source text of the node at `indices`.
+  fn value(&self, indices: &[usize]) -> &str {
+    let (node, start): (&Node, usize) = self.node(indices);
+    &self.text[start..start + node.len]
+  }
+}
+
+impl Node {
+  fn end(&self) -> usize {
+    self.offset + self.len
+  }
+
+  /// Returns the index of the child referenced by `token`.
+  ///
+  /// Like `serde_json`, the last occurrence of a duplicate key wins.
+  fn find(&self, token: &str) -> Result<usize> {
+    match self.data {
+      Data::Object(ref members) => members
+        .iter()
+        .rposition(|member| member.key == token)
+        .ok_or(Error::InvalidPointer),
+      Data::Array(ref items) => parse_idx(token, items.len()),
+      Data::Scalar => Err(Error::InvalidPointer),
+    }
+  }
+
+  fn child(&self, index: usize) -> &Node {
+    match self.data {
+      Data::Object(ref members) => &members[index].value,
+      Data::Array(ref items) => &items[index],
+      Data::Scalar => unreachable!(),
+    }
+  }
+
+  fn child_mut(&mut self, index: usize) -> &mut Node {
+    match self.data {
+      Data::Object(ref mut members) => &mut members[index].value,
+      Data::Array(ref mut items) => &mut items[index],
+      Data::Scalar => unreachable!(),
+    }
+  }
+
+  /// Resizes the node and moves its children from `from` onwards.
+  fn shift(&mut self, from: usize, removed: usize, inserted: usize) {
+    self.len = self.len + inserted - removed;
+
+    match self.data {
+      Data::Object(ref mut members) => {
+        for member in members.iter_mut().skip(from) {
+          member.offset = member.offset + inserted - removed;
+          member.value.offset = member.value.offset + inserted - removed;
+        }
+      }
+      Data::Array(ref mut items) => {
+        for item in items.iter_mut().skip(from) {
+          item.offset = item.offset + inserted - removed;
+        }
+      }
+      Data::Scalar => {}
+    }
+  }
+}
+
+/// Returns the whitespace immediately preceding `index`.
+fn whitespace(text: &str, index: usize) -> &str {
+  let prefix: &str = &text[..index];
+  &prefix[prefix.trim_end_matches(is_whitespace).len()..]
+}
+
+fn is_whitespace(char: char) -> bool {
+  matches!(char, ' ' | '\t' | '\n' | '\r')
+}
+
+fn to_string<T: serde::Serialize + ?Sized>(value: &T) -> Result<String> {
+  serde_json::to_string(value).map_err(|error| Error::InvalidValue {
+    path: Pointer::root(),
+    error,
+  })
+}
+
+fn from_str<T: serde::de::DeserializeOwned>(text: &str) -> Result<T> {
+  serde_json::from_str(text).map_err(|error| Error::InvalidValue {
+    path: Pointer::root(),
+    error,
+  })
+}
+
+/// Parses the well-formed JSON value `text`, placed at `offset` within its
+/// container.
+fn parse(text: &str, offset: usize) -> Result<Node> {
+  let mut node: Node = Parser { text, index: 0 }
+    .value(0)
+    .ok_or(Error::InvalidPointer)?;
+
+  node.offset += offset;
+
+  Ok(node)
+}
+
+struct Parser<'a> {
+  text: &'a str,
+  index: usize,
+}
+
+impl Parser<'_> {
+  fn peek(&self) -> Option<u8> {
+    self.text.as_bytes().get(self.index).copied()
+  }
+
+  fn skip_whitespace(&mut self) {
+    while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+      self.index += 1;
+    }
+  }
+
+  fn expect(&mut self, byte: u8) -> Option<()> {
+    self.skip_whitespace();
+
+    if self.peek()? == byte {
+      self.index += 1;
+      Some(())
+    } else {
+      None
+    }
+  }
+
+  /// Parses a value within the container starting at `base`.
+  fn value(&mut self, base: usize) -> Option<Node> {
+    self.skip_whitespace();
+
+    let start: usize = self.index;
+
+    let data: Data = match self.peek()? {
+      b'{' => Data::Object(self.object(start)?),
+      b'[' => Data::Array(self.array(start)?),
+      b'"' => {
+        self.string()?;
+        Data::Scalar
+      }
+      _ => {
+        while !matches!(
+          self.peek(),
+          None | Some(b',' | b']' | b'}' | b' ' | b'\t' | b'\n' | b'\r')
+        ) {
+          self.index += 1;
+        }
+
+        Data::Scalar
+      }
+    };
+
+    Some(Node {
+      offset: start - base,
+      len: self.index - start,
+      data,
+    })
+  }
+
+  fn object(&mut self, start: usize) -> Option<Vec<Member>> {
+    let mut members: Vec<Member> = Vec::new();
+
+    self.expect(b'{')?;
+    self.skip_whitespace();
+
+    if self.peek()? == b'}' {
+      self.index += 1;
+      return Some(members);
+    }
+
+    loop {
+      self.skip_whitespace();
+
+      let offset: usize = self.index - start;
+      let key: String = serde_json::from_str(self.string()?).ok()?;
+
+      self.expect(b':')?;
+
+      members.push(Member {
+        key,
+        offset,
+        value: self.value(start)?,
+      });
+
+      self.skip_whitespace();
+
+      match self.peek()? {
+        b',' => self.index += 1,
+        b'}' => break,
+        _ => return None,
+      }
+    }
+
+    self.index += 1;
+
+    Some(members)
+  }
+
+  fn array(&mut self, start: usize) -> Option<Vec<Node>> {
+    let mut items: Vec<Node> = Vec::new();
+
+    self.expect(b'[')?;
+    self.skip_whitespace();
+
+    if self.peek()? == b']' {
+      self.index += 1;
+      return Some(items);
+    }
+
+    loop {
+      items.push(self.value(start)?);
+
+      self.skip_whitespace();
+
+      match self.peek()? {
+        b',' => self.index += 1,
+        b']' => break,
+        _ => return None,
+      }
+    }
+
+    self.index += 1;
+
+    Some(items)
+  }
+
+  fn string(&mut self) -> Option<&str> {
+    let start: usize = self.index;
+
+    self.expect(b'"')?;
+
+    loop {
+      match self.peek()? {
+        b'\\' => self.index += 2,
+        b'"' => break,
+        _ => self.index += 1,
+      }
+    }
+
+    self.index += 1;
+
+    Some(&self.text[start..self.index])
+  }
+}
@@ -1,6 +1,10 @@
+use serde_json::Value;
+
+use crate::error::Error;
 use crate::error::Result;
 use crate::operation::OpAdd;
 use crate::operation::OpCopy;
+use crate::operation::OpCustom;
 use crate::operation::OpMove;
 use crate::operation::OpRemove;
 use crate::operation::OpReplace;
@@ -14,6 +18,45 @@ pub trait CanPatch {
   fn patch_remove(&mut self, op: OpRemove) -> Result<()>;
   fn patch_replace(&mut self, op: OpReplace) -> Result<()>;
   fn patch_test(&mut self, op: OpTest) -> Result<()>;
+
+  /// Applies a custom operation.
+  ///
+  /// By default, the value at the target location is read with
+  /// [`patch_get`][Self::patch_get] and written back with a `replace`.
+  fn patch_custom(&mut self, op: OpCustom) -> Result<()> {
+    let mut value: Value = self.patch_get(op.path())?;
+
+    op.apply(&mut value)?;
+
+    self.patch_replace(OpReplace {
+      path: op.path().into(),
+      value,
+    })
+  }
+
+  /// Applies an extension operation such as `inc` or `str_ins`.
+  ///
+  /// By default, the value at the target location is read with
+  /// [`patch_get`][Self::patch_get] and written back with a `replace`.
+  fn patch_extension(&mut self, op: Operation) -> Result<()> {
+    let mut value: Value = self.patch_get(op.path())?;
+
+    op.edit(&mut value)?;
+
+    self.patch_replace(OpReplace {
+      path: op.path().into(),
+      value,
+    })
+  }
+
+  /// Returns the JSON representation of the value at `path`.
+  ///
+  /// This is used to resolve the `from` location of `copy` and `move`
+  /// operations that span values of different types.
+  fn patch_get(&self, path: &str) -> Result<Value> {
+    let _: &str = path;
+    Err(Error::InvalidPointer)
+  }
 }
 
 impl<T> CanPatch for T
@@ -49,4 +92,14 @@ where
   fn patch_test(&mut self, op: OpTest) -> Result<()> {
     (self)(Operation::Test(op))
   }
+
+  #[inline]
+  fn patch_custom(&mut self, op: OpCustom) -> Result<()> {
+    (self)(Operation::Custom(op))
+  }
+
+  #[inline]
+  fn patch_extension(&mut self, op: Operation) -> Result<()> {
+    (self)(op)
+  }
 }
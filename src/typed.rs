@@ -0,0 +1,42 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use serde_path_to_error::Error as PathError;
+use serde_path_to_error::Segment;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::pointer::Pointer;
+
+/// Applies `f` to the JSON representation of `value`.
+///
+/// `value` is left unchanged if `f` fails or the result cannot be converted
+/// back into `T`.
+pub(crate) fn apply_typed<T>(value: &mut T, f: impl FnOnce(&mut Value) -> Result<()>) -> Result<()>
+where
+  T: Serialize + DeserializeOwned,
+{
+  let mut json: Value = serde_path_to_error::serialize(&*value, serde_json::value::Serializer)
+    .map_err(invalid_value)?;
+
+  f(&mut json)?;
+
+  *value = serde_path_to_error::deserialize(json).map_err(invalid_value)?;
+
+  Ok(())
+}
+
+fn invalid_value(error: PathError<serde_json::Error>) -> Error {
+  let path: Pointer =
+    Pointer::from_tokens(error.path().iter().filter_map(|segment| match segment {
+      Segment::Seq { index } => Some(index.to_string()),
+      Segment::Map { key } => Some(key.clone()),
+      Segment::Enum { variant } => Some(variant.clone()),
+      Segment::Unknown => None,
+    }));
+
+  Error::InvalidValue {
+    path,
+    error: error.into_inner(),
+  }
+}
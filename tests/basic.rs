@@ -1,6 +1,11 @@
+use json_patch::Condition;
+#[cfg(not(feature = "extensions"))]
+use json_patch::OpNumeric;
+use json_patch::OpTest;
 use json_patch::Operation;
 use json_patch::Patch;
 use serde_json::from_str;
+use serde_json::json;
 
 #[test]
 fn test_structure() {
@@ -35,3 +40,75 @@ fn test_structure() {
   let json: &str = r#"{"op": "test", "path": "/a/b/c", "value": "foo"}"#;
   assert!(matches!(from_str(json).unwrap(), Operation::Test(_)));
 }
+
+#[test]
+fn test_reference_tokens() {
+  let json: &str = r#"[{"op": "add", "path": "/a~1b~0", "value": 1}]"#;
+  let patch: Patch = from_str(json).unwrap();
+
+  // The final reference token is unescaped like every other token.
+  assert_eq!(patch.apply_ref(&json!({})).unwrap(), json!({"a/b~": 1}));
+
+  let json: &str = r#"[{"op": "remove", "path": "/a~1b~0"}]"#;
+  let patch: Patch = from_str(json).unwrap();
+
+  assert_eq!(patch.apply_ref(&json!({"a/b~": 1})).unwrap(), json!({}));
+
+  // Array indices consist of digits only.
+  for path in ["/+1", "/+1/a", "/1/a/+0", "/-0", "/ 1", "/01"] {
+    let json: String = format!(r#"[{{"op": "replace", "path": "{}", "value": 0}}]"#, path);
+    let patch: Patch = from_str(&json).unwrap();
+
+    assert!(patch.apply_ref(&json!([1, {"a": [2]}])).is_err());
+  }
+
+  let json: &str = r#"[{"op": "add", "path": "/+1", "value": 0}]"#;
+  let patch: Patch = from_str(json).unwrap();
+
+  assert!(patch.apply_ref(&json!([1])).is_err());
+}
+
+#[cfg(not(feature = "predicates"))]
+#[test]
+fn test_predicates_ignored() {
+  let json: &str = r#"[{"op": "test", "path": "/a", "value": 1, "not": true, "lt": 0}]"#;
+  let patch: Patch = from_str(json).unwrap();
+
+  assert!(patch.apply_ref(&json!({ "a": 1 })).is_ok());
+  assert!(from_str::<Patch>(r#"[{"op": "test", "path": "/a", "exists": true}]"#).is_err());
+}
+
+#[test]
+fn test_op_test_fields() {
+  // Conditions apply whether or not they can be parsed from a patch.
+  let op: OpTest = OpTest {
+    path: "/a".into(),
+    value: json!(null),
+    not: true,
+    condition: Some(Condition::Lt(1.into())),
+  };
+
+  let patch: Patch = Patch::from(vec![Operation::Test(op)]);
+
+  assert!(patch.clone().apply_ref(&json!({ "a": 1 })).is_ok());
+  assert!(patch.clone().apply_ref(&json!({ "a": 0 })).is_err());
+  assert_eq!(
+    serde_json::to_value(&patch).unwrap(),
+    json!([{ "op": "test", "path": "/a", "not": true, "lt": 1 }])
+  );
+}
+
+#[cfg(not(feature = "extensions"))]
+#[test]
+fn test_extensions_ignored() {
+  let patch: Patch = Patch::from(vec![Operation::Inc(OpNumeric {
+    path: "/a".into(),
+    value: 2.into(),
+  })]);
+
+  assert_eq!(
+    patch.apply_ref(&json!({ "a": 1 })).unwrap(),
+    json!({ "a": 3 })
+  );
+  assert!(from_str::<Patch>(r#"[{"op": "inc", "path": "/a", "value": 2}]"#).is_err());
+}
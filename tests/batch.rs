@@ -0,0 +1,82 @@
+mod fixtures;
+
+use json_patch::Error;
+use json_patch::Patch;
+use serde_json::json;
+use serde_json::Value;
+
+use self::fixtures::patch;
+
+fn docs() -> Vec<Value> {
+  vec![
+    json!({ "version": 1, "tenant": "a" }),
+    json!({ "version": 2, "tenant": "b" }),
+    json!({ "version": 1 }),
+    json!({ "version": 1, "tenant": "d" }),
+  ]
+}
+
+fn check(mut docs: Vec<Value>, results: Vec<Result<(), Error>>) {
+  assert_eq!(results.len(), 4);
+  assert!(results[0].is_ok());
+  assert!(matches!(results[1], Err(Error::InvalidTest)));
+  assert!(matches!(results[2], Err(Error::InvalidPointer)));
+  assert!(results[3].is_ok());
+
+  assert_eq!(
+    docs.remove(0),
+    json!({ "version": 2, "tenant": { "id": "a" } })
+  );
+  assert_eq!(docs.remove(0), json!({ "version": 2, "tenant": "b" }));
+  assert_eq!(docs.remove(0), json!({ "version": 1 }));
+  assert_eq!(
+    docs.remove(0),
+    json!({ "version": 2, "tenant": { "id": "d" } })
+  );
+}
+
+fn migration() -> Patch {
+  patch(json!([
+    { "op": "test", "path": "/version", "value": 1 },
+    { "op": "replace", "path": "/version", "value": 2 },
+    { "op": "move", "from": "/tenant", "path": "/id" },
+    { "op": "add", "path": "/tenant", "value": {} },
+    { "op": "move", "from": "/id", "path": "/tenant/id" },
+  ]))
+}
+
+#[test]
+fn test_apply_many() {
+  let mut docs: Vec<Value> = docs();
+  let results: Vec<Result<(), Error>> = migration().apply_many(&mut docs);
+
+  check(docs, results);
+}
+
+#[test]
+fn test_apply_many_uncompiled() {
+  // Patches rejected by `compile` still report errors as `apply_mut` does.
+  let patch: Patch = patch(json!([
+    { "op": "test", "path": "/version", "value": 1 },
+    { "op": "move", "from": "/tenant", "path": "/tenant/id" },
+  ]));
+
+  assert!(patch.compile().is_err());
+
+  let mut docs: Vec<Value> = docs();
+  let expected: Vec<Value> = docs.clone();
+  let results: Vec<Result<(), Error>> = patch.apply_many(&mut docs);
+
+  assert!(matches!(results[0], Err(Error::InvalidPointer)));
+  assert!(matches!(results[1], Err(Error::InvalidTest)));
+  assert_eq!(docs, expected);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_apply_many_par() {
+  let mut docs: Vec<Value> = docs();
+  let results: Vec<Result<(), Error>> = migration().apply_many_par(&mut docs);
+
+  check(docs, results);
+}
@@ -0,0 +1,102 @@
+mod fixtures;
+
+use json_patch::OperationRef;
+use json_patch::Patch;
+use json_patch::PatchRef;
+use serde_json::from_str;
+use serde_json::json;
+use serde_json::Value;
+use std::borrow::Cow;
+
+use self::fixtures::assert_same;
+use self::fixtures::fixtures;
+
+const PATCH: &str = r#"[
+  { "op": "add", "path": "/a~1b", "value": { "x": [1, 2.50] } },
+  { "op": "copy", "from": "/a~1b", "path": "/c" },
+  { "op": "move", "from": "/c", "path": "/d\u007e1e" },
+  { "op": "test", "path": "/d~1e/x/1", "value": 2.5 },
+  { "op": "remove", "path": "/a~1b", "extra": [true] }
+]"#;
+
+#[test]
+fn test_borrowed() {
+  let patch: PatchRef = from_str(PATCH).unwrap();
+  let ops: Vec<&OperationRef> = patch.iter().collect();
+
+  assert_eq!(patch.len(), 5);
+
+  assert!(matches!(
+    ops[0],
+    OperationRef::Add {
+      path: Cow::Borrowed("/a~1b"),
+      ..
+    }
+  ));
+  assert!(matches!(
+    ops[2],
+    OperationRef::Move {
+      from: Cow::Borrowed("/c"),
+      path: Cow::Owned(_)
+    }
+  ));
+
+  assert_eq!(ops[2].path(), "/d~1e");
+  assert_eq!(ops[1].from(), Some("/a~1b"));
+  assert_eq!(ops[0].value().unwrap().get(), r#"{ "x": [1, 2.50] }"#);
+  assert!(ops[4].value().is_none());
+}
+
+#[test]
+fn test_borrowed_apply() {
+  let patch: PatchRef = from_str(PATCH).unwrap();
+  let owned: Patch = from_str(PATCH).unwrap();
+
+  assert_eq!(patch.to_patch().unwrap(), owned);
+
+  let value: Value = json!({ "z": null });
+
+  assert_eq!(
+    patch.apply_ref(&value).unwrap(),
+    json!({ "z": null, "d/e": { "x": [1, 2.5] } })
+  );
+  assert_eq!(
+    patch.apply_ref(&value).unwrap(),
+    owned.apply_ref(&value).unwrap()
+  );
+}
+
+#[test]
+fn test_borrowed_serialize() {
+  let patch: PatchRef = from_str(PATCH).unwrap();
+  let owned: Patch = from_str(PATCH).unwrap();
+
+  assert_eq!(
+    serde_json::to_value(&patch).unwrap(),
+    serde_json::to_value(&owned).unwrap()
+  );
+}
+
+#[test]
+fn test_borrowed_invalid() {
+  let error = |input: &str| from_str::<PatchRef>(input).unwrap_err().to_string();
+
+  assert!(error(r#"[{ "op": "add", "path": "/a" }]"#).contains("missing field `value`"));
+  assert!(error(r#"[{ "op": "copy", "path": "/a" }]"#).contains("missing field `from`"));
+  assert!(error(r#"[{ "path": "/a" }]"#).contains("missing field `op`"));
+  assert!(error(r#"[{ "op": "drop", "path": "/a" }]"#).contains("unknown variant `drop`"));
+  assert!(error(r#"[{ "op": "inc", "path": "/a", "value": 1 }]"#).contains("unknown variant `inc`"));
+  assert!(
+    error(r#"[{ "op": "remove", "path": "/a", "path": "/b" }]"#).contains("duplicate field `path`")
+  );
+}
+
+#[test]
+fn test_fixtures() {
+  for (doc, patch) in fixtures() {
+    let text: String = serde_json::to_string(&patch).unwrap();
+    let borrowed: PatchRef = from_str(&text).unwrap();
+
+    assert_same(borrowed.apply_ref(&doc), patch.apply_ref(&doc));
+  }
+}
@@ -0,0 +1,125 @@
+mod fixtures;
+
+use json_patch::CompiledPatch;
+use json_patch::Error;
+use json_patch::Patch;
+use serde_json::json;
+use serde_json::Value;
+
+use self::fixtures::assert_same;
+use self::fixtures::fixtures;
+use self::fixtures::patch;
+
+#[test]
+fn test_compiled() {
+  let patch: Patch = patch(json!([
+    { "op": "test", "path": "/version", "value": 1 },
+    { "op": "replace", "path": "/version", "value": 2 },
+    { "op": "move", "from": "/a~1b", "path": "/tags/0" },
+    { "op": "copy", "from": "/tags", "path": "/c~0d" },
+    { "op": "add", "path": "/tags/-", "value": "z" },
+    { "op": "remove", "path": "/tags/1" },
+  ]));
+
+  let compiled: CompiledPatch = patch.compile().unwrap();
+
+  assert_eq!(compiled.len(), 6);
+
+  for index in 0..3 {
+    let mut value: Value = json!({ "version": 1, "a/b": index, "tags": ["x"] });
+    let expected: Value = patch.clone().apply_ref(&value).unwrap();
+
+    compiled.apply_mut(&mut value).unwrap();
+
+    assert_eq!(value, expected);
+    assert_eq!(
+      value,
+      json!({ "version": 2, "tags": [index, "z"], "c~d": [index, "x"] })
+    );
+  }
+
+  assert!(matches!(
+    compiled.apply_ref(&json!({ "version": 2 })),
+    Err(Error::InvalidTest)
+  ));
+}
+
+#[test]
+fn test_compiled_invalid() {
+  let invalid: &[Value] = &[
+    json!([{ "op": "add", "path": "a", "value": 1 }]),
+    json!([{ "op": "remove", "path": "" }]),
+    json!([{ "op": "move", "from": "", "path": "/a" }]),
+    json!([{ "op": "move", "from": "/a", "path": "/a/b" }]),
+    json!([{ "op": "copy", "from": "a", "path": "/b" }]),
+  ];
+
+  for value in invalid {
+    assert!(matches!(
+      patch(value.clone()).compile(),
+      Err(Error::InvalidPointer)
+    ));
+  }
+
+  let value: Value = json!({ "a": [1] });
+
+  assert!(matches!(
+    patch(json!([{ "op": "add", "path": "/a/01", "value": 1 }]))
+      .compile()
+      .unwrap()
+      .apply_ref(&value),
+    Err(Error::InvalidPointer)
+  ));
+  assert_eq!(
+    patch(json!([{ "op": "move", "from": "/a", "path": "/a" }]))
+      .compile()
+      .unwrap()
+      .apply_ref(&value)
+      .unwrap(),
+    value
+  );
+}
+
+/// Checks that the compiled patch behaves like the interpreted one.
+fn check(doc: &Value, patch: &Patch) {
+  let current: Result<Value, Error> = patch.compile().and_then(|compiled| compiled.apply_ref(doc));
+
+  assert_same(current, patch.clone().apply_ref(doc));
+}
+
+#[test]
+fn test_compiled_equivalence() {
+  let docs: &[Value] = &[
+    json!({}),
+    json!({ "a~2": 1, "a~": [2], "b": { "a~": 3 } }),
+    json!([1, [2]]),
+  ];
+
+  let patches: &[Value] = &[
+    json!([{ "op": "add", "path": "/a~2", "value": 1 }]),
+    json!([{ "op": "add", "path": "/a~/-", "value": 1 }]),
+    json!([{ "op": "add", "path": "/a~2/b", "value": 1 }]),
+    json!([{ "op": "remove", "path": "/a~2" }]),
+    json!([{ "op": "replace", "path": "/b/a~0", "value": 4 }]),
+    json!([{ "op": "test", "path": "/a~", "value": [2] }]),
+    json!([{ "op": "test", "path": "/a~0", "value": [2] }]),
+    json!([{ "op": "move", "from": "/a~", "path": "/a~0/0" }]),
+    json!([{ "op": "move", "from": "/a~2", "path": "/c~1d" }]),
+    json!([{ "op": "copy", "from": "/b", "path": "/b~/c" }]),
+    json!([{ "op": "copy", "from": "/1", "path": "/1/-" }]),
+    json!([{ "op": "remove", "path": "/1/0~" }]),
+  ];
+
+  for doc in docs {
+    for value in patches {
+      check(doc, &patch(value.clone()));
+    }
+  }
+}
+
+#[test]
+fn test_fixtures() {
+  for (doc, patch) in fixtures() {
+    check(&doc, &patch);
+  }
+}
@@ -0,0 +1,318 @@
+#![cfg(feature = "std")]
+
+use json_patch::register;
+use json_patch::Error;
+use json_patch::Extension;
+use json_patch::History;
+use json_patch::OpCustom;
+use json_patch::Operation;
+use json_patch::Patch;
+use json_patch::Result;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::from_str;
+use serde_json::from_value;
+use serde_json::json;
+use serde_json::to_string;
+use serde_json::to_value;
+use serde_json::Value;
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+struct Increment {
+  path: String,
+  #[serde(default = "one")]
+  by: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+struct AppendUnique {
+  path: String,
+  value: Value,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+struct RenameKey {
+  path: String,
+  from: String,
+  to: String,
+}
+
+fn one() -> i64 {
+  1
+}
+
+impl Extension for Increment {
+  const NAME: &'static str = "increment";
+
+  fn path(&self) -> &str {
+    &self.path
+  }
+
+  fn path_mut(&mut self) -> &mut String {
+    &mut self.path
+  }
+
+  fn apply(&self, target: &mut Value) -> Result<()> {
+    let value: i64 = target.as_i64().ok_or(Error::InvalidTest)?;
+    *target = Value::from(value + self.by);
+    Ok(())
+  }
+}
+
+impl Extension for AppendUnique {
+  const NAME: &'static str = "append_unique";
+
+  fn path(&self) -> &str {
+    &self.path
+  }
+
+  fn path_mut(&mut self) -> &mut String {
+    &mut self.path
+  }
+
+  fn apply(&self, target: &mut Value) -> Result<()> {
+    let array: &mut Vec<Value> = target.as_array_mut().ok_or(Error::InvalidPointer)?;
+
+    if !array.contains(&self.value) {
+      array.push(self.value.clone());
+    }
+
+    Ok(())
+  }
+}
+
+impl Extension for RenameKey {
+  const NAME: &'static str = "rename_key";
+
+  fn path(&self) -> &str {
+    &self.path
+  }
+
+  fn path_mut(&mut self) -> &mut String {
+    &mut self.path
+  }
+
+  fn apply(&self, target: &mut Value) -> Result<()> {
+    let object = target.as_object_mut().ok_or(Error::InvalidPointer)?;
+    let value: Value = object.remove(&self.from).ok_or(Error::InvalidPointer)?;
+
+    object.insert(self.to.clone(), value);
+
+    Ok(())
+  }
+}
+
+fn setup() {
+  register::<Increment>().unwrap();
+  register::<AppendUnique>().unwrap();
+  register::<RenameKey>().unwrap();
+}
+
+fn patch() -> Patch {
+  from_value(json!([
+    { "op": "test", "path": "/count", "value": 1 },
+    { "op": "increment", "path": "/count", "by": 2 },
+    { "op": "increment", "path": "/count" },
+    { "op": "append_unique", "path": "/tags", "value": "a" },
+    { "op": "append_unique", "path": "/tags", "value": "c" },
+    { "op": "rename_key", "path": "", "from": "tags", "to": "labels" },
+  ]))
+  .unwrap()
+}
+
+#[test]
+fn test_custom() {
+  setup();
+
+  let value: Value = json!({ "count": 1, "tags": ["a", "b"] });
+  let expected: Value = json!({ "count": 4, "labels": ["a", "b", "c"] });
+  let patch: Patch = patch();
+
+  assert_eq!(patch.clone().apply_ref(&value).unwrap(), expected);
+  assert_eq!(patch.clone().apply_overlay(&value).unwrap(), expected);
+  assert_eq!(
+    patch.compile().unwrap().apply_ref(&value).unwrap(),
+    expected
+  );
+
+  let text: String = patch
+    .clone()
+    .apply_text(&to_string(&value).unwrap())
+    .unwrap();
+
+  assert_eq!(serde_json::from_str::<Value>(&text).unwrap(), expected);
+
+  let mut history: History = History::new(value.clone());
+
+  history.apply(patch).unwrap();
+  assert_eq!(history.value(), &expected);
+  assert!(history.undo().unwrap());
+  assert_eq!(history.value(), &value);
+}
+
+#[test]
+fn test_custom_structure() {
+  setup();
+
+  let patch: Patch = patch();
+  let operation: &Operation = patch.iter().nth(2).unwrap();
+
+  match operation {
+    Operation::Custom(op) => {
+      assert_eq!(op.name(), "increment");
+      assert_eq!(op.path(), "/count");
+      assert_eq!(
+        op.downcast_ref::<Increment>(),
+        Some(&Increment {
+          path: "/count".into(),
+          by: 1,
+        })
+      );
+      assert!(op.downcast_ref::<RenameKey>().is_none());
+    }
+    _ => panic!("expected a custom operation"),
+  }
+
+  assert_eq!(
+    operation,
+    &Operation::Custom(OpCustom::new(Increment {
+      path: "/count".into(),
+      by: 1,
+    }))
+  );
+
+  assert_eq!(
+    to_value(&patch).unwrap()[1],
+    json!({ "op": "increment", "path": "/count", "by": 2 })
+  );
+  assert_eq!(
+    from_value::<Patch>(to_value(&patch).unwrap()).unwrap(),
+    patch
+  );
+}
+
+#[test]
+fn test_custom_errors() {
+  setup();
+
+  let error: String = from_value::<Patch>(json!([{ "op": "decrement", "path": "/count" }]))
+    .unwrap_err()
+    .to_string();
+
+  assert_eq!(
+    error,
+    "unknown variant `decrement`, expected one of `add`, `remove`, `replace`, `move`, `copy`, `test`"
+  );
+
+  let error: String = from_value::<Patch>(json!([{ "op": "rename_key", "path": "", "from": "a" }]))
+    .unwrap_err()
+    .to_string();
+
+  assert_eq!(error, "missing field `to`");
+
+  let error: String =
+    from_str::<Patch>(r#"[{ "op": "increment", "path": "/count", "op": "add" }]"#)
+      .unwrap_err()
+      .to_string();
+
+  assert_eq!(error, "duplicate field `op` at line 1 column 44");
+
+  let value: Value = json!({ "count": "1", "tags": [] });
+  let patch: Patch = from_value(json!([
+    { "op": "append_unique", "path": "/tags", "value": "a" },
+    { "op": "increment", "path": "/count" },
+  ]))
+  .unwrap();
+
+  assert!(matches!(patch.apply_ref(&value), Err(Error::InvalidTest)));
+
+  assert!(matches!(
+    from_value::<Patch>(json!([{ "op": "increment", "path": "/missing" }]))
+      .unwrap()
+      .apply_ref(&value),
+    Err(Error::InvalidPointer)
+  ));
+}
+
+#[test]
+fn test_custom_member_order() {
+  setup();
+
+  let patch: Patch = from_value(json!([
+    { "path": "/count", "by": 2, "op": "increment" },
+    { "path": "/tags/-", "op": "add", "value": "c" },
+    { "value": 3, "path": "/count", "op": "test" },
+  ]))
+  .unwrap();
+
+  assert_eq!(
+    patch.apply_ref(&json!({ "count": 1, "tags": [] })).unwrap(),
+    json!({ "count": 3, "tags": ["c"] })
+  );
+}
+
+#[test]
+fn test_register_conflict() {
+  #[derive(Clone, Debug, Deserialize, Serialize)]
+  struct Add {
+    path: String,
+  }
+
+  impl Extension for Add {
+    const NAME: &'static str = "add";
+
+    fn path(&self) -> &str {
+      &self.path
+    }
+
+    fn path_mut(&mut self) -> &mut String {
+      &mut self.path
+    }
+
+    fn apply(&self, _: &mut Value) -> Result<()> {
+      Ok(())
+    }
+  }
+
+  #[derive(Clone, Debug, Deserialize, Serialize)]
+  struct Other(Add);
+
+  impl Extension for Other {
+    const NAME: &'static str = "increment";
+
+    fn path(&self) -> &str {
+      &self.0.path
+    }
+
+    fn path_mut(&mut self) -> &mut String {
+      &mut self.0.path
+    }
+
+    fn apply(&self, _: &mut Value) -> Result<()> {
+      Ok(())
+    }
+  }
+
+  setup();
+  setup();
+
+  assert!(matches!(
+    register::<Add>(),
+    Err(Error::DuplicateOperation("add"))
+  ));
+  assert!(matches!(
+    register::<Other>(),
+    Err(Error::DuplicateOperation("increment"))
+  ));
+  assert_eq!(patch().iter().nth(1).unwrap().path(), "/count");
+}
+
+#[test]
+fn test_custom_in_place() {
+  setup();
+
+  let mut value: Value = json!({ "count": 1, "tags": ["a", "b"] });
+
+  patch().apply_in_place(&mut value).unwrap();
+  assert_eq!(value, json!({ "count": 4, "labels": ["a", "b", "c"] }));
+}
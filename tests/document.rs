@@ -0,0 +1,168 @@
+use json_patch::Document;
+use json_patch::Error;
+use json_patch::NodeKind;
+use json_patch::Patch;
+use json_patch::Result;
+use json_patch::TreeValue;
+use serde_json::from_value;
+use serde_json::json;
+use serde_json::to_string;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Tree {
+  Leaf(String),
+  List(Vec<Tree>),
+  Map(BTreeMap<String, Tree>),
+}
+
+impl Document for Tree {
+  fn from_value(value: Value) -> Result<Self> {
+    match value {
+      Value::Array(inner) => inner
+        .into_iter()
+        .map(Self::from_value)
+        .collect::<Result<_>>()
+        .map(Self::List),
+      Value::Object(inner) => inner
+        .into_iter()
+        .map(|(key, value)| Self::from_value(value).map(|value| (key, value)))
+        .collect::<Result<_>>()
+        .map(Self::Map),
+      Value::Null => Err(Error::UnsupportedValue),
+      Value::String(inner) => Ok(Self::Leaf(inner)),
+      other => Ok(Self::Leaf(other.to_string())),
+    }
+  }
+
+  fn eq_value(&self, value: &Value) -> bool {
+    matches!(Self::from_value(value.clone()), Ok(other) if *self == other)
+  }
+
+  fn kind(&self) -> NodeKind {
+    match self {
+      Self::Leaf(_) => NodeKind::Other,
+      Self::List(_) => NodeKind::Array,
+      Self::Map(_) => NodeKind::Object,
+    }
+  }
+
+  fn object_get(&self, key: &str) -> Option<&Self> {
+    match self {
+      Self::Map(inner) => inner.get(key),
+      _ => None,
+    }
+  }
+
+  fn object_get_mut(&mut self, key: &str) -> Option<&mut Self> {
+    match self {
+      Self::Map(inner) => inner.get_mut(key),
+      _ => None,
+    }
+  }
+
+  fn object_insert(&mut self, key: String, value: Self) -> Option<Self> {
+    match self {
+      Self::Map(inner) => inner.insert(key, value),
+      _ => None,
+    }
+  }
+
+  fn object_remove(&mut self, key: &str) -> Option<Self> {
+    match self {
+      Self::Map(inner) => inner.remove(key),
+      _ => None,
+    }
+  }
+
+  fn array_len(&self) -> usize {
+    match self {
+      Self::List(inner) => inner.len(),
+      _ => 0,
+    }
+  }
+
+  fn array_get(&self, index: usize) -> Option<&Self> {
+    match self {
+      Self::List(inner) => inner.get(index),
+      _ => None,
+    }
+  }
+
+  fn array_get_mut(&mut self, index: usize) -> Option<&mut Self> {
+    match self {
+      Self::List(inner) => inner.get_mut(index),
+      _ => None,
+    }
+  }
+
+  fn array_insert(&mut self, index: usize, value: Self) {
+    if let Self::List(inner) = self {
+      inner.insert(index, value);
+    }
+  }
+
+  fn array_remove(&mut self, index: usize) -> Self {
+    match self {
+      Self::List(inner) => inner.remove(index),
+      _ => Self::Leaf(String::new()),
+    }
+  }
+}
+
+#[test]
+fn test_custom_document() {
+  let mut value: Tree = Tree::from_value(json!({"a": {"b": ["x", "y"]}, "c~/d": "z"})).unwrap();
+
+  let patch: Patch = from_value(json!([
+    {"op": "test", "path": "/a/b/1", "value": "y"},
+    {"op": "add", "path": "/a/b/-", "value": "w"},
+    {"op": "remove", "path": "/a/b/0"},
+    {"op": "move", "from": "/c~0~1d", "path": "/e"},
+    {"op": "copy", "from": "/a", "path": "/f"},
+    {"op": "replace", "path": "/f/b", "value": 1},
+  ]))
+  .unwrap();
+
+  patch.apply_mut(&mut value).unwrap();
+
+  assert_eq!(
+    value,
+    Tree::from_value(json!({"a": {"b": ["y", "w"]}, "e": "z", "f": {"b": "1"}})).unwrap()
+  );
+
+  let patch: Patch = from_value(json!([{"op": "test", "path": "/e", "value": "y"}])).unwrap();
+  assert!(patch.apply_mut(&mut value).is_err());
+}
+
+#[test]
+fn test_tree_value() {
+  let mut value: TreeValue = TreeValue::from(json!({"b": [1, null], "a": {"c": true}}));
+
+  let patch: Patch = from_value(json!([
+    {"op": "test", "path": "/b", "value": [1, null]},
+    {"op": "add", "path": "/b/1", "value": "x"},
+    {"op": "move", "from": "/a/c", "path": "/d"},
+    {"op": "replace", "path": "/a", "value": {"e": 1.5}},
+  ]))
+  .unwrap();
+
+  patch.apply_mut(&mut value).unwrap();
+
+  assert_eq!(
+    Value::from(value.clone()),
+    json!({"a": {"e": 1.5}, "b": [1, "x", null], "d": true})
+  );
+  assert_eq!(
+    to_string(&value).unwrap(),
+    r#"{"a":{"e":1.5},"b":[1,"x",null],"d":true}"#
+  );
+  assert_eq!(
+    from_value::<TreeValue>(json!({"a": [null]})).unwrap(),
+    TreeValue::from(json!({"a": [null]}))
+  );
+
+  let patch: Patch = from_value(json!([{"op": "test", "path": "/d", "value": 1}])).unwrap();
+  assert!(patch.apply_mut(&mut value).is_err());
+}
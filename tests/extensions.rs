@@ -0,0 +1,294 @@
+#![cfg(feature = "extensions")]
+
+mod fixtures;
+
+#[cfg(feature = "std")]
+use json_patch::register;
+use json_patch::Error;
+#[cfg(feature = "std")]
+use json_patch::Extension;
+use json_patch::History;
+use json_patch::OpNumeric;
+use json_patch::OpStrIns;
+use json_patch::Operation;
+use json_patch::Patch;
+#[cfg(feature = "std")]
+use serde::Deserialize;
+#[cfg(feature = "std")]
+use serde::Serialize;
+use serde_json::from_value;
+use serde_json::json;
+use serde_json::to_value;
+use serde_json::Value;
+
+use self::fixtures::apply_all;
+use self::fixtures::patch;
+
+fn numeric(op: &str, target: Value, operand: Value) -> Result<Value, Error> {
+  let patch: Patch = patch(json!([{ "op": op, "path": "/a", "value": operand }]));
+  apply_all(&json!({ "a": target }), patch).map(|value| value["a"].clone())
+}
+
+#[test]
+fn test_numeric() {
+  assert_eq!(numeric("inc", json!(1), json!(2)).unwrap(), json!(3));
+  assert_eq!(numeric("inc", json!(1), json!(0.5)).unwrap(), json!(1.5));
+  assert_eq!(numeric("dec", json!(1), json!(2)).unwrap(), json!(-1));
+  assert_eq!(numeric("mul", json!(3), json!(-2)).unwrap(), json!(-6));
+  assert_eq!(numeric("mul", json!(1.5), json!(2)).unwrap(), json!(3.0));
+  assert_eq!(numeric("min", json!(3), json!(2)).unwrap(), json!(2));
+  assert_eq!(numeric("min", json!(3), json!(4.5)).unwrap(), json!(3));
+  assert_eq!(numeric("max", json!(3), json!(4.5)).unwrap(), json!(4.5));
+  assert_eq!(
+    numeric("max", json!(u64::MAX), json!(1)).unwrap(),
+    json!(u64::MAX)
+  );
+
+  assert_eq!(
+    numeric("inc", json!(i64::MAX), json!(1)).unwrap(),
+    json!(i64::MAX as u64 + 1)
+  );
+  assert_eq!(
+    numeric("inc", json!(u64::MAX), json!(0)).unwrap(),
+    json!(u64::MAX)
+  );
+  assert_eq!(
+    numeric("dec", json!(u64::MAX), json!(u64::MAX)).unwrap(),
+    json!(0)
+  );
+  assert_eq!(
+    numeric("dec", json!(i64::MIN), json!(u64::MAX)).unwrap(),
+    json!(i64::MIN as f64 - u64::MAX as f64)
+  );
+  assert_eq!(
+    numeric("inc", json!(u64::MAX), json!(1)).unwrap(),
+    json!(u64::MAX as f64 + 1.0)
+  );
+  assert_eq!(
+    numeric("mul", json!(u64::MAX), json!(-1)).unwrap(),
+    json!(-(u64::MAX as f64))
+  );
+
+  assert!(matches!(
+    numeric("inc", json!("1"), json!(1)),
+    Err(Error::InvalidTarget)
+  ));
+  assert!(matches!(
+    numeric("inc", json!([1]), json!(1)),
+    Err(Error::InvalidTarget)
+  ));
+  assert!(matches!(
+    numeric("mul", json!(f64::MAX), json!(2)),
+    Err(Error::UnsupportedValue)
+  ));
+  assert!(from_value::<Patch>(json!([{ "op": "inc", "path": "/a", "value": "1" }])).is_err());
+}
+
+#[test]
+fn test_string() {
+  let value: Value = json!({ "text": "héllo wörld" });
+
+  let output: Value = apply_all(
+    &value,
+    patch(json!([
+      { "op": "str_del", "path": "/text", "pos": 6, "value": "wö" },
+      { "op": "str_ins", "path": "/text", "pos": 6, "value": "wo" },
+      { "op": "str_ins", "path": "/text", "pos": 11, "value": "!" },
+      { "op": "str_ins", "path": "/text", "pos": 0, "value": "¡" },
+    ])),
+  )
+  .unwrap();
+
+  assert_eq!(output, json!({ "text": "¡héllo world!" }));
+
+  let errors: &[Value] = &[
+    json!({ "op": "str_ins", "path": "/text", "pos": 12, "value": "!" }),
+    json!({ "op": "str_del", "path": "/text", "pos": 1, "value": "el" }),
+    json!({ "op": "str_del", "path": "/text", "pos": 10, "value": "dd" }),
+    json!({ "op": "str_del", "path": "/text", "pos": 12, "value": "" }),
+    json!({ "op": "str_ins", "path": "", "pos": 0, "value": "!" }),
+  ];
+
+  for op in errors {
+    assert!(matches!(
+      apply_all(&value, patch(json!([op]))),
+      Err(Error::InvalidTarget)
+    ));
+  }
+
+  assert!(matches!(
+    apply_all(
+      &value,
+      patch(json!([{ "op": "str_ins", "path": "/missing", "pos": 0, "value": "!" }]))
+    ),
+    Err(Error::InvalidPointer)
+  ));
+}
+
+#[test]
+fn test_structure() {
+  let ops: Value = json!([
+    { "op": "inc", "path": "/a", "value": 1 },
+    { "op": "dec", "path": "/a", "value": 1 },
+    { "op": "mul", "path": "/a", "value": 2.5 },
+    { "op": "min", "path": "/a", "value": 0 },
+    { "op": "max", "path": "/a", "value": 9 },
+    { "op": "str_ins", "path": "/b", "pos": 1, "value": "x" },
+    { "op": "str_del", "path": "/b", "pos": 1, "value": "x" },
+    { "op": "add_to_set", "path": "/c", "value": [1] },
+    { "op": "pull", "path": "/c", "value": null },
+  ]);
+
+  let patch: Patch = patch(ops.clone());
+
+  assert_eq!(
+    patch.iter().next().unwrap(),
+    &Operation::Inc(OpNumeric {
+      path: "/a".into(),
+      value: 1.into(),
+    })
+  );
+  assert_eq!(
+    patch.iter().nth(5).unwrap(),
+    &Operation::StrIns(OpStrIns {
+      path: "/b".into(),
+      pos: 1,
+      value: "x".into(),
+    })
+  );
+  assert_eq!(to_value(&patch).unwrap(), ops);
+
+  let error: String = from_value::<Patch>(json!([{ "op": "div", "path": "/a", "value": 1 }]))
+    .unwrap_err()
+    .to_string();
+
+  assert_eq!(
+    error,
+    "unknown variant `div`, expected one of `add`, `remove`, `replace`, `move`, `copy`, `test`"
+  );
+}
+
+#[test]
+fn test_history() {
+  let value: Value = json!({ "count": 1, "text": "ab" });
+  let mut history: History = History::new(value.clone());
+
+  history
+    .apply(patch(json!([
+      { "op": "inc", "path": "/count", "value": 2 },
+      { "op": "str_ins", "path": "/text", "pos": 1, "value": "x" },
+    ])))
+    .unwrap();
+
+  assert_eq!(history.value(), &json!({ "count": 3, "text": "axb" }));
+  assert!(history.undo().unwrap());
+  assert_eq!(history.value(), &value);
+}
+
+#[test]
+fn test_set() {
+  let value: Value = json!({ "tags": ["a", { "b": 1 }, "a", "c"] });
+
+  let output: Value = apply_all(
+    &value,
+    patch(json!([
+      { "op": "add_to_set", "path": "/tags", "value": "c" },
+      { "op": "add_to_set", "path": "/tags", "value": { "b": 1 } },
+      { "op": "add_to_set", "path": "/tags", "value": "d" },
+      { "op": "pull", "path": "/tags", "value": "a" },
+      { "op": "pull", "path": "/tags", "value": "e" },
+    ])),
+  )
+  .unwrap();
+
+  assert_eq!(output, json!({ "tags": [{ "b": 1 }, "c", "d"] }));
+
+  assert!(matches!(
+    apply_all(
+      &value,
+      patch(json!([{ "op": "add_to_set", "path": "", "value": "a" }]))
+    ),
+    Err(Error::InvalidTarget)
+  ));
+  assert!(matches!(
+    apply_all(
+      &value,
+      patch(json!([{ "op": "pull", "path": "/tags/0", "value": "a" }]))
+    ),
+    Err(Error::InvalidTarget)
+  ));
+  assert!(matches!(
+    apply_all(
+      &value,
+      patch(json!([{ "op": "pull", "path": "/labels", "value": "a" }]))
+    ),
+    Err(Error::InvalidPointer)
+  ));
+
+  let mut history: History = History::new(value.clone());
+
+  history
+    .apply(patch(
+      json!([{ "op": "pull", "path": "/tags", "value": "a" }]),
+    ))
+    .unwrap();
+
+  assert_eq!(history.value(), &json!({ "tags": [{ "b": 1 }, "c"] }));
+  assert!(history.undo().unwrap());
+  assert_eq!(history.value(), &value);
+}
+
+#[test]
+fn test_in_place() {
+  let operations: Patch = patch(json!([
+    { "op": "inc", "path": "/count", "value": 2 },
+    { "op": "str_ins", "path": "/text", "pos": 1, "value": "x" },
+  ]));
+
+  let mut value: Value = json!({ "count": 1, "text": "ab" });
+
+  operations.clone().apply_in_place(&mut value).unwrap();
+  assert_eq!(value, json!({ "count": 3, "text": "axb" }));
+
+  let mut seen: Vec<Operation> = Vec::new();
+
+  operations
+    .clone()
+    .apply_fun(|op: Operation| {
+      seen.push(op);
+      Ok(())
+    })
+    .unwrap();
+
+  assert_eq!(Patch::from(seen), operations);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_register_builtin() {
+  #[derive(Clone, Debug, Deserialize, Serialize)]
+  struct Inc {
+    path: String,
+  }
+
+  impl Extension for Inc {
+    const NAME: &'static str = "inc";
+
+    fn path(&self) -> &str {
+      &self.path
+    }
+
+    fn path_mut(&mut self) -> &mut String {
+      &mut self.path
+    }
+
+    fn apply(&self, _: &mut Value) -> Result<(), Error> {
+      Ok(())
+    }
+  }
+
+  assert!(matches!(
+    register::<Inc>(),
+    Err(Error::DuplicateOperation("inc"))
+  ));
+}
@@ -0,0 +1,79 @@
+// ==
+// https://github.com/json-patch/json-patch-tests
+// ==
+#![allow(dead_code)]
+
+use core::fmt::Debug;
+use serde::Deserialize;
+use serde_json::from_slice;
+use serde_json::from_str;
+use serde_json::from_value;
+use serde_json::to_string;
+use serde_json::Value;
+
+use json_patch::Error;
+use json_patch::Overlay;
+use json_patch::Patch;
+
+const T1: &[u8] = include_bytes!("spec_tests.json");
+const T2: &[u8] = include_bytes!("tests.json");
+
+#[derive(Deserialize)]
+struct Test {
+  #[serde(default)]
+  disabled: bool,
+  doc: Value,
+  patch: Value,
+}
+
+/// Returns the document and patch of every enabled conformance test whose
+/// patch is well-formed.
+pub fn fixtures() -> Vec<(Value, Patch)> {
+  [T1, T2]
+    .iter()
+    .flat_map(|fixture| from_slice::<Vec<Test>>(fixture).unwrap())
+    .filter(|test| !test.disabled)
+    .filter_map(|test| Some((test.doc, from_value(test.patch).ok()?)))
+    .collect()
+}
+
+/// Parses a patch from its JSON representation.
+pub fn patch(value: Value) -> Patch {
+  from_value(value).unwrap()
+}
+
+/// Asserts that `current` has the same outcome as `expected`, comparing errors
+/// by their message.
+pub fn assert_same<T: Debug + PartialEq>(current: Result<T, Error>, expected: Result<T, Error>) {
+  match (expected, current) {
+    (Ok(expected), Ok(current)) => assert_eq!(current, expected),
+    (Err(expected), Err(current)) => assert_eq!(current.to_string(), expected.to_string()),
+    (expected, current) => panic!("{:?} != {:?}", current, expected),
+  }
+}
+
+/// Applies `patch` to `value`, asserting that applying it to the text of
+/// `value`, as an overlay and compiled gives the same result.
+pub fn apply_all(value: &Value, patch: Patch) -> Result<Value, Error> {
+  let text: String = to_string(value).unwrap();
+
+  assert_same(
+    patch
+      .clone()
+      .apply_text(&text)
+      .map(|text| from_str(&text).unwrap()),
+    patch.clone().apply_ref(value),
+  );
+  assert_same(
+    patch.clone().apply_overlay(value).map(Overlay::into_value),
+    patch.clone().apply_ref(value),
+  );
+  assert_same(
+    patch
+      .compile()
+      .and_then(|compiled| compiled.apply_ref(value)),
+    patch.clone().apply_ref(value),
+  );
+
+  patch.apply_ref(value)
+}
@@ -0,0 +1,154 @@
+mod fixtures;
+
+use json_patch::Error;
+use json_patch::History;
+use json_patch::Patch;
+use serde_json::json;
+use serde_json::Value;
+
+use self::fixtures::fixtures;
+use self::fixtures::patch;
+
+fn push(index: usize) -> Patch {
+  patch(json!([{ "op": "add", "path": "/items/-", "value": index }]))
+}
+
+#[test]
+fn test_history_undo_redo() {
+  let mut history: History = History::new(json!({ "a": 1 }));
+
+  assert!(!history.undo().unwrap());
+
+  history
+    .apply(patch(json!([
+      { "op": "add", "path": "/b", "value": [1, 2, 3] },
+      { "op": "move", "from": "/b/0", "path": "/b/-" },
+      { "op": "replace", "path": "/a", "value": { "c": true } },
+    ])))
+    .unwrap();
+  history
+    .apply(patch(json!([
+      { "op": "copy", "from": "/a", "path": "/b/1" },
+      { "op": "remove", "path": "/a/c" },
+    ])))
+    .unwrap();
+
+  assert_eq!(history.version(), 2);
+  assert_eq!(
+    history.value(),
+    &json!({ "a": {}, "b": [2, { "c": true }, 3, 1] })
+  );
+  assert_eq!(
+    history.inverse(2),
+    Some(&patch(json!([
+      { "op": "add", "path": "/a/c", "value": true },
+      { "op": "remove", "path": "/b/1" },
+    ])))
+  );
+
+  assert!(history.undo().unwrap());
+  assert_eq!(
+    history.value(),
+    &json!({ "a": { "c": true }, "b": [2, 3, 1] })
+  );
+  assert!(history.undo().unwrap());
+  assert_eq!(history.value(), &json!({ "a": 1 }));
+  assert!(!history.undo().unwrap());
+
+  assert!(history.redo().unwrap());
+  assert!(history.redo().unwrap());
+  assert!(!history.redo().unwrap());
+  assert_eq!(
+    history.value(),
+    &json!({ "a": {}, "b": [2, { "c": true }, 3, 1] })
+  );
+}
+
+#[test]
+fn test_history_branch() {
+  let mut history: History = History::new(json!({ "items": [] }));
+
+  for index in 0..4 {
+    history.apply(push(index)).unwrap();
+  }
+
+  history.checkout(1).unwrap();
+
+  assert_eq!(history.apply(push(9)).unwrap(), 2);
+  assert_eq!(history.latest(), 2);
+  assert_eq!(history.value(), &json!({ "items": [0, 9] }));
+  assert!(!history.redo().unwrap());
+}
+
+#[test]
+fn test_history_checkout() {
+  for interval in [0, 1, 3, 32] {
+    let mut history: History = History::new(json!({ "items": [] })).with_interval(interval);
+
+    for index in 0..10 {
+      history.apply(push(index)).unwrap();
+    }
+
+    for version in [4, 0, 10, 7, 3, 9] {
+      let expected: Value = json!({ "items": (0..version).collect::<Vec<_>>() });
+
+      assert_eq!(history.get(version).unwrap(), expected);
+
+      history.checkout(version).unwrap();
+
+      assert_eq!(history.version(), version);
+      assert_eq!(history.value(), &expected);
+    }
+
+    assert!(matches!(history.checkout(11), Err(Error::InvalidVersion)));
+    assert!(matches!(history.get(11), Err(Error::InvalidVersion)));
+    assert_eq!(history.version(), 9);
+  }
+}
+
+#[test]
+fn test_history_failed() {
+  let mut history: History = History::new(json!(null));
+
+  assert!(history
+    .apply(patch(json!([
+      { "op": "add", "path": "/a", "value": 1 },
+      { "op": "test", "path": "/a", "value": 2 },
+    ])))
+    .is_err());
+
+  assert_eq!(history.value(), &json!(null));
+  assert_eq!(history.latest(), 0);
+
+  history
+    .apply(patch(json!([{ "op": "add", "path": "/a", "value": 1 }])))
+    .unwrap();
+  history.undo().unwrap();
+
+  assert_eq!(history.value(), &json!(null));
+}
+
+#[test]
+fn test_fixtures() {
+  for (doc, patch) in fixtures() {
+    let mut history: History = History::new(doc.clone());
+
+    match patch.clone().apply_ref(&doc) {
+      Ok(expected) => {
+        history.apply(patch).unwrap();
+        assert_eq!(history.value(), &expected);
+        assert!(history.undo().unwrap());
+        assert_eq!(history.value(), &doc);
+        assert!(history.redo().unwrap());
+        assert_eq!(history.value(), &expected);
+      }
+      Err(expected) => {
+        assert_eq!(
+          history.apply(patch).unwrap_err().to_string(),
+          expected.to_string()
+        );
+        assert_eq!(history.value(), &doc);
+      }
+    }
+  }
+}
@@ -0,0 +1,158 @@
+#![cfg(feature = "std")]
+
+mod fixtures;
+
+use json_patch::Error;
+use json_patch::Extension;
+use json_patch::LogEntry;
+use json_patch::LogReader;
+use json_patch::LogWriter;
+use json_patch::OpCustom;
+use json_patch::Operation;
+use json_patch::Result;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use serde_json::Value;
+
+use self::fixtures::patch;
+
+/// A custom operation that does not serialize to an object.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Touch(String);
+
+impl Extension for Touch {
+  const NAME: &'static str = "touch";
+
+  fn path(&self) -> &str {
+    &self.0
+  }
+
+  fn path_mut(&mut self) -> &mut String {
+    &mut self.0
+  }
+
+  fn apply(&self, _: &mut Value) -> Result<()> {
+    Ok(())
+  }
+}
+
+#[test]
+fn test_log_roundtrip() {
+  let entries: Vec<LogEntry> = vec![
+    LogEntry::new(patch(json!([{ "op": "add", "path": "/a", "value": 1 }])))
+      .with_seq(1)
+      .with_timestamp("2021-01-01T00:00:00Z"),
+    LogEntry::new(patch(json!([{ "op": "remove", "path": "/a" }]))).with_seq(2),
+    LogEntry::new(patch(json!([]))),
+  ];
+
+  let mut writer: LogWriter<Vec<u8>> = LogWriter::new(Vec::new());
+
+  for entry in entries.iter() {
+    writer.write(entry).unwrap();
+  }
+
+  let output: Vec<u8> = writer.into_inner();
+
+  assert_eq!(
+    String::from_utf8(output.clone()).unwrap(),
+    concat!(
+      r#"{"seq":1,"timestamp":"2021-01-01T00:00:00Z","patch":[{"op":"add","path":"/a","value":1}]}"#,
+      "\n",
+      r#"{"seq":2,"patch":[{"op":"remove","path":"/a"}]}"#,
+      "\n",
+      r#"{"patch":[]}"#,
+      "\n",
+    )
+  );
+
+  let read: Vec<LogEntry> = LogReader::new(output.as_slice())
+    .collect::<Result<_, _>>()
+    .unwrap();
+
+  assert_eq!(read, entries);
+}
+
+#[test]
+fn test_log_framing() {
+  let input: &str = r#"
+[{ "op": "add", "path": "/a", "value": [] }]
+{ "op": "add", "path": "/a/-", "value": 1 }
+
+{ "seq": 3, "patch": { "op": "add", "path": "/a/-", "value": 2 } }
+{ "seq": 4, "timestamp": "now", "patch": [{ "op": "copy", "from": "/a", "path": "/b" }] }
+"#;
+
+  let entries: Vec<LogEntry> = LogReader::new(input.as_bytes())
+    .collect::<Result<_, _>>()
+    .unwrap();
+
+  assert_eq!(entries.len(), 4);
+  assert_eq!(entries[1].seq, None);
+  assert_eq!(entries[1].patch.len(), 1);
+  assert_eq!(entries[2].seq, Some(3));
+  assert_eq!(entries[3].timestamp.as_deref(), Some("now"));
+
+  let mut value: Value = json!({});
+
+  assert_eq!(
+    LogReader::new(input.as_bytes()).replay(&mut value).unwrap(),
+    4
+  );
+  assert_eq!(value, json!({ "a": [1, 2], "b": [1, 2] }));
+}
+
+#[test]
+fn test_log_replay_error() {
+  let input: &str = concat!(
+    r#"{ "op": "add", "path": "/a", "value": 1 }"#,
+    "\n",
+    r#"{ "op": "add", "path": "/b" }"#,
+    "\n",
+    r#"{ "op": "add", "path": "/c", "value": 1 }"#,
+    "\n",
+  );
+
+  let mut value: Value = json!({});
+
+  assert!(matches!(
+    LogReader::new(input.as_bytes()).replay(&mut value),
+    Err(Error::InvalidPatch(_))
+  ));
+  assert_eq!(value, json!({ "a": 1 }));
+
+  let input: &str = concat!(
+    r#"{ "op": "add", "path": "/a", "value": 1 }"#,
+    "\n",
+    r#"[{ "op": "add", "path": "/b", "value": 2 }, { "op": "remove", "path": "/c" }]"#,
+    "\n",
+  );
+
+  let mut value: Value = json!({});
+
+  assert!(matches!(
+    LogReader::new(input.as_bytes()).replay(&mut value),
+    Err(Error::InvalidPointer)
+  ));
+  assert_eq!(value, json!({ "a": 1 }));
+
+  let input: &str = r#"{ "seq": 1 }"#;
+
+  assert!(matches!(
+    LogReader::new(input.as_bytes()).next(),
+    Some(Err(Error::InvalidPatch(_)))
+  ));
+}
+
+#[test]
+fn test_log_write_error() {
+  let operation: Operation = Operation::Custom(OpCustom::new(Touch("/a".into())));
+  let mut writer: LogWriter<Vec<u8>> = LogWriter::new(Vec::new());
+
+  assert!(matches!(
+    writer.write(&LogEntry::new(vec![operation])),
+    Err(Error::InvalidPatch(_))
+  ));
+  assert!(writer.into_inner().is_empty());
+}
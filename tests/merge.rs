@@ -1,5 +1,16 @@
+use json_patch::merge_diff;
 use json_patch::merge_ref;
+use json_patch::merge_with;
+use json_patch::Error;
+use json_patch::MergeArrays;
+use json_patch::MergeNulls;
+use json_patch::MergeOptions;
+use json_patch::MergePatch;
+use json_patch::Patch;
+use serde_json::from_str;
 use serde_json::json;
+use serde_json::to_value;
+use serde_json::Value;
 
 #[test]
 #[rustfmt::skip]
@@ -20,3 +31,177 @@ fn test_rfc7396() {
   assert_eq!(merge_ref(&json!([1, 2]), &json!({"a":"b","c":null})), json!({"a":"b"}));
   assert_eq!(merge_ref(&json!({}), &json!({"a":{"bb":{"ccc":null}}})), json!({"a":{"bb":{}}}));
 }
+
+#[test]
+fn test_merge_patch() {
+  let patch: MergePatch = from_str(r#"{"a": {"b": "d", "c": null}}"#).unwrap();
+
+  assert_eq!(
+    patch.apply_ref(&json!({"a": {"b": "c", "c": 1}})),
+    json!({"a": {"b": "d"}})
+  );
+  assert_eq!(
+    to_value(&patch).unwrap(),
+    json!({"a": {"b": "d", "c": null}})
+  );
+  assert_eq!(MergePatch::from(patch.clone().into_value()), patch);
+
+  let mut value: Value = json!([1]);
+  patch.apply_mut(&mut value);
+  assert_eq!(value, json!({"a": {"b": "d"}}));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_merge_diff() {
+  let cases: &[(Value, Value)] = &[
+    (json!({"a":"b"}), json!({"a":"c"})),
+    (json!({"a":"b"}), json!({"a":"b","b":"c"})),
+    (json!({"a":"b","b":"c"}), json!({"b":"c"})),
+    (json!({"a":["b"]}), json!({"a":"c"})),
+    (json!({"a": {"b": "c", "c": 1}}), json!({"a": {"b": "d"}})),
+    (json!(["a", "b"]), json!(["c", "d"])),
+    (json!({"a":"b"}), json!(["c"])),
+    (json!({"a":"foo"}), json!(null)),
+    (json!([1, 2]), json!({"a":{"b":[null]}})),
+    (json!({"a":1}), json!({"a":1})),
+  ];
+
+  for (from, to) in cases {
+    assert_eq!(merge_diff(from, to).unwrap().apply_ref(from), *to);
+  }
+
+  assert_eq!(merge_diff(&json!({"a":1,"b":2}), &json!({"b":3})).unwrap().into_value(), json!({"a":null,"b":3}));
+  assert_eq!(merge_diff(&json!({"a":1}), &json!({"a":1})).unwrap().into_value(), json!({}));
+  assert_eq!(merge_diff(&json!({"a":1}), &json!({"a":null})).unwrap_err().to_string(), Error::InvalidMergePatch.to_string());
+  assert!(merge_diff(&json!({}), &json!({"a":{"b":null}})).is_err());
+  assert!(merge_diff(&json!(1), &json!({"a":null})).is_err());
+
+  assert_eq!(merge_diff(&json!({"e":null}), &json!({"e":null,"a":1})).unwrap().into_value(), json!({"a":1}));
+  assert_eq!(merge_diff(&json!({"a":{"e":null}}), &json!({"a":{"e":null,"b":2}})).unwrap().into_value(), json!({"a":{"b":2}}));
+  assert!(merge_diff(&json!({"e":null}), &json!({"e":null,"a":{"b":null}})).is_err());
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_merge_patch_conversion() {
+  let cases: &[(Value, Value)] = &[
+    (json!({"a":"b"}), json!({"a":"c"})),
+    (json!({"a":"b"}), json!({"b":"c"})),
+    (json!({"a":"b","b":"c"}), json!({"a":null,"c":null})),
+    (json!({"a":["b"]}), json!({"a":"c"})),
+    (json!({"a": {"b": "c"}}), json!({"a": {"b": "d","c": null}})),
+    (json!(["a", "b"]), json!(["c", "d"])),
+    (json!({"a":"foo"}), json!(null)),
+    (json!([1, 2]), json!({"a":"b","c":null})),
+    (json!({}), json!({"a":{"bb":{"ccc":null}}})),
+    (json!({"a":1}), json!({"a":{"b":{"c":null,"d":2}}})),
+  ];
+
+  for (base, patch) in cases {
+    let patch: MergePatch = MergePatch::new(patch.clone());
+    let expected: Value = patch.apply_ref(base);
+
+    assert_eq!(patch.to_patch(base).apply_ref(base).unwrap(), expected);
+    assert_eq!(patch.to_patch(base).to_merge_patch(base).unwrap().apply_ref(base), expected);
+  }
+
+  let patch: MergePatch = MergePatch::new(json!({"a":1}));
+  assert_eq!(patch.to_patch(&json!({"e": null})).apply_ref(&json!({"e": null})).unwrap(), json!({"e":null,"a":1}));
+  assert_eq!(patch.to_patch(&json!({"e": null})).to_merge_patch(&json!({"e": null})).unwrap().into_value(), json!({"a":1}));
+
+  let patch: Patch = from_str(r#"[{"op": "add", "path": "/a", "value": null}]"#).unwrap();
+  assert!(patch.to_merge_patch(&json!({})).is_err());
+
+  let patch: Patch = from_str(r#"[{"op": "remove", "path": "/a"}]"#).unwrap();
+  assert!(patch.to_merge_patch(&json!({})).is_err());
+  assert_eq!(patch.to_merge_patch(&json!({"a": 1})).unwrap().into_value(), json!({"a": null}));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_merge_with() {
+  fn merge(value: Value, patch: Value, options: &MergeOptions) -> Value {
+    let mut value: Value = value;
+    merge_with(&mut value, &patch, options);
+    value
+  }
+
+  let options: MergeOptions = MergeOptions::default();
+
+  assert_eq!(merge(json!({"a":"b","b":"c"}), json!({"a":null}), &options), json!({"b":"c"}));
+  assert_eq!(merge(json!({"a":[1]}), json!({"a":[2]}), &options), json!({"a":[2]}));
+  assert_eq!(merge(json!({"a":1}), json!({"a":{"b":2}}), &options), json!({"a":{"b":2}}));
+
+  let options: MergeOptions = MergeOptions {
+    nulls: MergeNulls::Set { delete: json!({"$delete": true}) },
+    ..MergeOptions::default()
+  };
+
+  assert_eq!(merge(json!({"a":"b","b":"c"}), json!({"a":null,"b":{"$delete":true}}), &options), json!({"a":null}));
+  assert_eq!(merge(json!({}), json!({"a":{"b":null}}), &options), json!({"a":{"b":null}}));
+
+  let options: MergeOptions = MergeOptions {
+    arrays: MergeArrays::Append,
+    ..MergeOptions::default()
+  };
+
+  assert_eq!(merge(json!({"a":[1,2]}), json!({"a":[2,3]}), &options), json!({"a":[1,2,2,3]}));
+  assert_eq!(merge(json!({"a":1}), json!({"a":[2,3]}), &options), json!({"a":[2,3]}));
+
+  let options: MergeOptions = MergeOptions {
+    arrays: MergeArrays::Union,
+    ..MergeOptions::default()
+  };
+
+  assert_eq!(merge(json!({"a":[1,2]}), json!({"a":[2,3]}), &options), json!({"a":[1,2,3]}));
+
+  let options: MergeOptions = MergeOptions {
+    preserve: true,
+    ..MergeOptions::default()
+  };
+
+  assert_eq!(merge(json!({"a":1,"b":[2]}), json!({"a":{"c":1},"b":{"c":1}}), &options), json!({"a":1,"b":[2]}));
+  assert_eq!(merge(json!({}), json!({"a":{"c":1}}), &options), json!({"a":{"c":1}}));
+  assert_eq!(merge(json!("a"), json!({"c":1}), &options), json!("a"));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_merge_compose() {
+  let cases: &[(Value, Value)] = &[
+    (json!({"a":1}), json!({"b":2})),
+    (json!({"a":{"b":1,"c":2}}), json!({"a":{"c":null,"d":3}})),
+    (json!({"a":{"b":1}}), json!({"a":null})),
+    (json!({"a":null}), json!({"a":2})),
+    (json!({"a":{"b":1}}), json!({"a":[1]})),
+    (json!([1]), json!("x")),
+    (json!({"a":1}), json!(null)),
+  ];
+
+  let bases: &[Value] = &[
+    json!({}),
+    json!(null),
+    json!({"a":{"b":0,"e":0},"f":0}),
+    json!({"a":"b"}),
+  ];
+
+  for (lhs, rhs) in cases {
+    let lhs: MergePatch = MergePatch::new(lhs.clone());
+    let rhs: MergePatch = MergePatch::new(rhs.clone());
+    let composed: MergePatch = MergePatch::compose(&lhs, &rhs).unwrap();
+
+    for base in bases {
+      assert_eq!(composed.apply_ref(base), rhs.apply_ref(&lhs.apply_ref(base)));
+    }
+  }
+
+  let lhs: MergePatch = MergePatch::new(json!({"a":null}));
+  assert!(lhs.compose(&MergePatch::new(json!({"a":{"b":1}}))).is_err());
+
+  let lhs: MergePatch = MergePatch::new(json!({"a":1}));
+  assert!(lhs.compose(&MergePatch::new(json!({"a":{"b":1}}))).is_err());
+
+  let lhs: MergePatch = MergePatch::new(json!([1]));
+  assert!(lhs.compose(&MergePatch::new(json!({"b":1}))).is_err());
+}
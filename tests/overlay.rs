@@ -0,0 +1,96 @@
+mod fixtures;
+
+use json_patch::Overlay;
+use json_patch::Patch;
+use serde_json::json;
+use serde_json::to_string;
+use serde_json::Value;
+use std::borrow::Cow;
+
+use self::fixtures::assert_same;
+use self::fixtures::fixtures;
+use self::fixtures::patch;
+
+#[test]
+fn test_overlay() {
+  let base: Value = json!({
+    "a": { "b": [1, 2, 3], "c": "d" },
+    "e": { "f": null },
+  });
+
+  let overlay: Overlay = patch(json!([
+    { "op": "add", "path": "/a/b/1", "value": 9 },
+    { "op": "copy", "from": "/e", "path": "/g" },
+    { "op": "move", "from": "/a/c", "path": "/h" },
+    { "op": "replace", "path": "/g/f", "value": true },
+    { "op": "test", "path": "/a", "value": { "b": [1, 9, 2, 3] } },
+  ]))
+  .apply_overlay(&base)
+  .unwrap();
+
+  let expected: Value = json!({
+    "a": { "b": [1, 9, 2, 3] },
+    "e": { "f": null },
+    "g": { "f": true },
+    "h": "d",
+  });
+
+  assert_eq!(overlay, expected);
+  assert_eq!(overlay.to_value(), expected);
+  assert_eq!(to_string(&overlay).unwrap(), to_string(&expected).unwrap());
+  assert_eq!(overlay.clone().into_value(), expected);
+
+  assert!(
+    matches!(overlay.get("/e"), Some(Cow::Borrowed(value)) if std::ptr::eq(value, &base["e"]))
+  );
+  assert!(
+    matches!(overlay.get("/a/b/3"), Some(Cow::Borrowed(value)) if std::ptr::eq(value, &base["a"]["b"][2]))
+  );
+  assert!(
+    matches!(overlay.get("/h"), Some(Cow::Borrowed(value)) if std::ptr::eq(value, &base["a"]["c"]))
+  );
+  assert_eq!(overlay.get("/g"), Some(Cow::Owned(json!({ "f": true }))));
+  assert_eq!(overlay.get("/a/c"), None);
+
+  assert_eq!(base["a"]["c"], "d");
+}
+
+#[test]
+fn test_overlay_stacked() {
+  let base: Value = json!([1, 2]);
+  let mut overlay: Overlay = Overlay::new(&base);
+
+  assert_eq!(overlay, base);
+
+  overlay
+    .apply(patch(json!([{ "op": "add", "path": "/-", "value": 3 }])))
+    .unwrap();
+  overlay
+    .apply(patch(json!([{ "op": "remove", "path": "/0" }])))
+    .unwrap();
+
+  assert_eq!(overlay, json!([2, 3]));
+  assert!(overlay
+    .apply(patch(json!([{ "op": "test", "path": "/0", "value": 1 }])))
+    .is_err());
+
+  let base: Value = json!("scalar");
+
+  assert_eq!(Patch::from(Vec::new()).apply_overlay(&base).unwrap(), base);
+  assert_eq!(
+    patch(json!([{ "op": "add", "path": "/a", "value": 1 }]))
+      .apply_overlay(&base)
+      .unwrap(),
+    json!({ "a": 1 })
+  );
+}
+
+#[test]
+fn test_fixtures() {
+  for (doc, patch) in fixtures() {
+    assert_same(
+      patch.clone().apply_overlay(&doc).map(Overlay::into_value),
+      patch.apply_ref(&doc),
+    );
+  }
+}
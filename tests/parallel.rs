@@ -0,0 +1,143 @@
+#![cfg(feature = "rayon")]
+
+mod fixtures;
+
+use json_patch::Error;
+use json_patch::Patch;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+use self::fixtures::assert_same;
+use self::fixtures::fixtures;
+use self::fixtures::patch;
+
+fn check(value: Value, patch: Patch) {
+  let mut sequential: Value = value.clone();
+  let mut parallel: Value = value;
+
+  let expected: Result<(), Error> = patch.clone().apply_mut(&mut sequential);
+  let current: Result<(), Error> = patch.apply_par(&mut parallel);
+
+  assert_same(current, expected);
+  assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn test_parallel_independent() {
+  let value: Value = (0..100)
+    .map(|index| (format!("k{}", index), json!({ "n": index, "items": [] })))
+    .collect::<Map<String, Value>>()
+    .into();
+
+  let operations: Vec<Value> = (0..100)
+    .flat_map(|index| {
+      vec![
+        json!({ "op": "replace", "path": format!("/k{}/n", index), "value": index * 2 }),
+        json!({ "op": "add", "path": format!("/k{}/items/-", index), "value": index }),
+        json!({ "op": "move", "from": format!("/k{}/n", index), "path": format!("/k{}/m", index) }),
+        json!({ "op": "test", "path": format!("/k{}/m", index), "value": index * 2 }),
+      ]
+    })
+    .collect();
+
+  check(value.clone(), patch(Value::Array(operations)));
+
+  check(
+    value.clone(),
+    patch(json!([
+      { "op": "remove", "path": "/k1" },
+      { "op": "add", "path": "/new", "value": 1 },
+      { "op": "add", "path": "/k1", "value": 2 },
+      { "op": "add", "path": "/a~1b", "value": 3 },
+    ])),
+  );
+}
+
+#[test]
+fn test_parallel_sequential() {
+  let value: Value = json!({ "a": { "x": 1 }, "b": [1, 2] });
+
+  check(
+    value.clone(),
+    patch(json!([
+      { "op": "copy", "from": "/a", "path": "/b/0" },
+      { "op": "replace", "path": "/a/x", "value": 2 },
+    ])),
+  );
+  check(
+    value.clone(),
+    patch(json!([
+      { "op": "move", "from": "/b", "path": "/a/b" },
+      { "op": "add", "path": "/b", "value": 3 },
+    ])),
+  );
+  check(
+    value.clone(),
+    patch(json!([
+      { "op": "replace", "path": "/a/x", "value": 2 },
+      { "op": "replace", "path": "", "value": [] },
+      { "op": "add", "path": "/-", "value": 3 },
+    ])),
+  );
+  check(
+    json!([1, 2, 3]),
+    patch(json!([
+      { "op": "remove", "path": "/0" },
+      { "op": "replace", "path": "/1", "value": 4 },
+    ])),
+  );
+  check(
+    json!(null),
+    patch(json!([
+      { "op": "add", "path": "/a", "value": 1 },
+      { "op": "add", "path": "/b", "value": 2 },
+    ])),
+  );
+}
+
+#[test]
+fn test_parallel_error() {
+  let value: Value = json!({ "a": 1, "b": 2, "c": 3 });
+
+  check(
+    value.clone(),
+    patch(json!([
+      { "op": "replace", "path": "/a", "value": 0 },
+      { "op": "test", "path": "/b", "value": 0 },
+      { "op": "remove", "path": "/c/missing" },
+    ])),
+  );
+  check(
+    value,
+    patch(json!([
+      { "op": "remove", "path": "/a/missing" },
+      { "op": "test", "path": "/b", "value": 0 },
+    ])),
+  );
+}
+
+#[test]
+fn test_fixtures() {
+  for (doc, patch) in fixtures() {
+    check(doc, patch);
+  }
+}
+
+#[test]
+fn test_parallel_error_partial() {
+  let value: Value = (0..20)
+    .map(|index| (format!("k{}", index), json!(index)))
+    .collect::<Map<String, Value>>()
+    .into();
+
+  // Every member but `k0` is replaced, the patch then fails on `k0` and every
+  // later operation must not be applied.
+  let operations: Vec<Value> = (1..20)
+    .map(|index| json!({ "op": "replace", "path": format!("/k{}", index), "value": -1 }))
+    .chain(Some(json!({ "op": "test", "path": "/k0", "value": 1 })))
+    .chain((1..20).map(|index| json!({ "op": "remove", "path": format!("/k{}", index) })))
+    .collect();
+
+  check(value, patch(Value::Array(operations)));
+}
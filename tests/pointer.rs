@@ -0,0 +1,167 @@
+use json_patch::Patch;
+use json_patch::Pointer;
+use serde_json::from_value;
+use serde_json::json;
+
+#[test]
+fn test_pointer() {
+  let pointer: Pointer = Pointer::new("/a~1b/c~0d/0").unwrap();
+
+  assert_eq!(pointer.tokens().collect::<Vec<_>>(), ["a/b", "c~d", "0"]);
+  assert_eq!(Pointer::from_tokens(["a/b", "c~d", "0"]), pointer);
+  assert_eq!(pointer.parent().unwrap().as_str(), "/a~1b/c~0d");
+  assert_eq!(pointer.last().unwrap(), "0");
+
+  assert!(Pointer::new("").unwrap().is_root());
+  assert!(Pointer::new("a").is_err());
+  assert!(Pointer::new("/a~2").is_err());
+  assert!(Pointer::new("/a~").is_err());
+
+  let prefix: Pointer = Pointer::new("/a").unwrap();
+
+  assert!(prefix.is_prefix_of(&Pointer::new("/a/b").unwrap()));
+  assert!(prefix.is_prefix_of(&Pointer::new("/a").unwrap()));
+  assert!(!prefix.is_prefix_of(&Pointer::new("/ab").unwrap()));
+  assert!(!Pointer::new("/a/b").unwrap().is_prefix_of(&prefix));
+  assert_eq!(
+    Pointer::new("/a/b")
+      .unwrap()
+      .strip_prefix(&prefix)
+      .unwrap()
+      .as_str(),
+    "/b"
+  );
+}
+
+#[test]
+fn test_prefixed() {
+  let patch: Patch = from_value(json!([
+    {"op": "add", "path": "/a", "value": 1},
+    {"op": "move", "from": "/a", "path": "/b"},
+    {"op": "replace", "path": "", "value": {}},
+  ]))
+  .unwrap();
+
+  let prefix: Pointer = Pointer::new("/widgets/0").unwrap();
+  let mounted: Patch = patch.prefixed(&prefix).unwrap();
+
+  let expected: Patch = from_value(json!([
+    {"op": "add", "path": "/widgets/0/a", "value": 1},
+    {"op": "move", "from": "/widgets/0/a", "path": "/widgets/0/b"},
+    {"op": "replace", "path": "/widgets/0", "value": {}},
+  ]))
+  .unwrap();
+
+  assert_eq!(mounted, expected);
+  assert_eq!(mounted.strip_prefix(&prefix).unwrap(), patch);
+  assert!(mounted
+    .strip_prefix(&Pointer::new("/widgets/1").unwrap())
+    .is_err());
+  assert!(mounted
+    .strip_prefix(&Pointer::new("/widgets/0/a").unwrap())
+    .is_err());
+
+  let mut value = json!({"widgets": [{}]});
+  mounted.apply_mut(&mut value).unwrap();
+  assert_eq!(value, json!({"widgets": [{}]}));
+
+  for path in ["a", "/a~2", "/a~"] {
+    let patch: Patch = from_value(json!([{"op": "remove", "path": path}])).unwrap();
+    assert!(patch.prefixed(&prefix).is_err());
+  }
+
+  let patch: Patch = from_value(json!([{"op": "copy", "from": "a", "path": "/b"}])).unwrap();
+  assert!(patch.prefixed(&prefix).is_err());
+}
+
+#[test]
+fn test_partition() {
+  let patch: Patch = from_value(json!([
+    {"op": "test", "path": "", "value": {}},
+    {"op": "test", "path": "/spec", "value": {}},
+    {"op": "add", "path": "/spec/replicas", "value": 3},
+    {"op": "copy", "from": "/metadata/name", "path": "/metadata/label"},
+    {"op": "remove", "path": "/spectral"},
+  ]))
+  .unwrap();
+
+  let (spec, rest): (Patch, Patch) = patch.partition(&Pointer::new("/spec").unwrap()).unwrap();
+
+  let expected: Patch = from_value(json!([
+    {"op": "test", "path": "", "value": {}},
+    {"op": "test", "path": "/spec", "value": {}},
+    {"op": "add", "path": "/spec/replicas", "value": 3},
+  ]))
+  .unwrap();
+
+  assert_eq!(spec, expected);
+
+  let expected: Patch = from_value(json!([
+    {"op": "test", "path": "", "value": {}},
+    {"op": "copy", "from": "/metadata/name", "path": "/metadata/label"},
+    {"op": "remove", "path": "/spectral"},
+  ]))
+  .unwrap();
+
+  assert_eq!(rest, expected);
+  assert_eq!(
+    patch.filter(|operation| operation.from().is_some()).len(),
+    1
+  );
+
+  let patch: Patch = from_value(json!([
+    {"op": "move", "from": "/metadata/name", "path": "/spec/name"},
+  ]))
+  .unwrap();
+
+  assert!(patch.partition(&Pointer::new("/spec").unwrap()).is_err());
+
+  let patch: Patch = from_value(json!([
+    {"op": "replace", "path": "", "value": {}},
+  ]))
+  .unwrap();
+
+  assert!(patch.partition(&Pointer::new("/spec").unwrap()).is_err());
+
+  // The test sees the new label, which the `/spec` side never adds.
+  let patch: Patch = from_value(json!([
+    {"op": "add", "path": "/metadata/label", "value": "a"},
+    {"op": "test", "path": "", "value": {"metadata": {"label": "a"}, "spec": {}}},
+    {"op": "add", "path": "/spec/replicas", "value": 3},
+  ]))
+  .unwrap();
+
+  assert!(patch.partition(&Pointer::new("/spec").unwrap()).is_err());
+}
+
+#[test]
+fn test_partition_by() {
+  let patch: Patch = from_value(json!([
+    {"op": "test", "path": "/spec/replicas", "value": 1},
+    {"op": "add", "path": "/spec/replicas", "value": 3},
+    {"op": "copy", "from": "/metadata/name", "path": "/metadata/label"},
+    {"op": "remove", "path": "/status"},
+  ]))
+  .unwrap();
+
+  let (spec, rest): (Patch, Patch) = patch
+    .partition_by(|path| path.starts_with("/spec/"))
+    .unwrap();
+
+  assert_eq!(
+    spec,
+    from_value(json!([
+      {"op": "test", "path": "/spec/replicas", "value": 1},
+      {"op": "add", "path": "/spec/replicas", "value": 3},
+    ]))
+    .unwrap()
+  );
+  assert_eq!(rest.len(), 2);
+
+  let (metadata, _): (Patch, Patch) = patch
+    .partition_by(|path| path.starts_with("/metadata/"))
+    .unwrap();
+
+  assert_eq!(metadata.len(), 1);
+  assert!(patch.partition_by(|path| path == "/metadata/name").is_err());
+}
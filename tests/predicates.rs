@@ -0,0 +1,200 @@
+#![cfg(feature = "predicates")]
+
+mod fixtures;
+
+use json_patch::Condition;
+use json_patch::Error;
+use json_patch::OpTest;
+use json_patch::Operation;
+use json_patch::Patch;
+use json_patch::PatchRef;
+use json_patch::ValueType;
+use serde_json::from_str;
+use serde_json::from_value;
+use serde_json::json;
+use serde_json::to_string;
+use serde_json::to_value;
+use serde_json::Value;
+
+use self::fixtures::apply_all;
+use self::fixtures::patch;
+
+fn check(value: &Value, patch: Patch) -> bool {
+  apply_all(value, patch).is_ok()
+}
+
+fn test(member: Value) -> Patch {
+  let mut op: Value = json!({ "op": "test", "path": "/a" });
+  op.as_object_mut()
+    .unwrap()
+    .extend(member.as_object().unwrap().clone());
+  patch(json!([op]))
+}
+
+#[test]
+fn test_not() {
+  let value: Value = json!({ "a": 1 });
+
+  assert!(check(&value, test(json!({ "value": 2, "not": true }))));
+  assert!(!check(&value, test(json!({ "value": 1, "not": true }))));
+  assert!(check(&value, test(json!({ "value": 1, "not": false }))));
+  assert!(!check(&json!({}), test(json!({ "value": 1, "not": true }))));
+}
+
+#[test]
+fn test_type() {
+  let cases: &[(Value, &str)] = &[
+    (json!(null), "null"),
+    (json!(true), "boolean"),
+    (json!(1.5), "number"),
+    (json!("a"), "string"),
+    (json!([1]), "array"),
+    (json!({ "b": 1 }), "object"),
+  ];
+
+  for (value, kind) in cases {
+    let value: Value = json!({ "a": value });
+
+    for (_, other) in cases {
+      let patch: Patch = test(json!({ "type": other }));
+      assert_eq!(check(&value, patch), kind == other);
+    }
+
+    assert!(!check(&value, test(json!({ "type": kind, "not": true }))));
+  }
+
+  assert!(!check(&json!({}), test(json!({ "type": "null" }))));
+  assert!(from_value::<Patch>(json!([{ "op": "test", "path": "", "type": "integer" }])).is_err());
+}
+
+#[test]
+fn test_exists() {
+  let value: Value = json!({ "a": null });
+
+  assert!(check(&value, test(json!({ "exists": true }))));
+  assert!(!check(&value, test(json!({ "exists": false }))));
+  assert!(check(&json!({}), test(json!({ "exists": false }))));
+  assert!(!check(&json!({}), test(json!({ "exists": true }))));
+  assert!(check(
+    &json!({}),
+    test(json!({ "exists": true, "not": true }))
+  ));
+  assert!(check(&json!([]), test(json!({ "exists": false }))));
+}
+
+#[test]
+fn test_compare() {
+  let value: Value = json!({ "a": 10 });
+
+  assert!(check(&value, test(json!({ "lt": 11 }))));
+  assert!(check(&value, test(json!({ "lt": 10.5 }))));
+  assert!(!check(&value, test(json!({ "lt": 10 }))));
+  assert!(check(&value, test(json!({ "gt": -1 }))));
+  assert!(check(&value, test(json!({ "gt": 9.99 }))));
+  assert!(!check(&value, test(json!({ "gt": 10 }))));
+  assert!(check(&value, test(json!({ "gt": 10, "not": true }))));
+  assert!(check(
+    &json!({ "a": u64::MAX }),
+    test(json!({ "gt": i64::MAX }))
+  ));
+
+  assert!(!check(&json!({ "a": "10" }), test(json!({ "lt": 11 }))));
+  assert!(!check(&json!({ "a": [10] }), test(json!({ "lt": 11 }))));
+  assert!(!check(&json!({}), test(json!({ "lt": 11 }))));
+
+  // Comparisons with anything but a number fail even if negated.
+  assert!(!check(
+    &json!({ "a": "10" }),
+    test(json!({ "lt": 11, "not": true }))
+  ));
+  assert!(!check(
+    &json!({ "a": null }),
+    test(json!({ "gt": 11, "not": true }))
+  ));
+  assert!(!check(
+    &json!({ "a": {} }),
+    test(json!({ "gt": 11, "not": true }))
+  ));
+  assert!(!check(&json!({}), test(json!({ "lt": 11, "not": true }))));
+}
+
+#[test]
+fn test_apply() {
+  let mut value: Value = json!({ "count": 3 });
+
+  let patch: Patch = patch(json!([
+    { "op": "test", "path": "/count", "type": "number" },
+    { "op": "test", "path": "/count", "lt": 10 },
+    { "op": "test", "path": "/limit", "exists": false },
+    { "op": "add", "path": "/limit", "value": 10 },
+  ]));
+
+  patch.clone().apply_mut(&mut value).unwrap();
+  assert_eq!(value, json!({ "count": 3, "limit": 10 }));
+
+  assert!(matches!(
+    patch.apply_mut(&mut value),
+    Err(Error::InvalidTest)
+  ));
+}
+
+#[test]
+fn test_invalid() {
+  let errors: &[(Value, &str)] = &[
+    (
+      json!({ "lt": 1, "gt": 0 }),
+      "expected at most one of `type`, `exists`, `lt` or `gt`",
+    ),
+    (
+      json!({ "value": 1, "exists": true }),
+      "unexpected field `value` in conditional test",
+    ),
+    (json!({ "not": true }), "missing field `value`"),
+  ];
+
+  for (member, message) in errors {
+    let mut op: Value = json!({ "op": "test", "path": "/a" });
+    op.as_object_mut()
+      .unwrap()
+      .extend(member.as_object().unwrap().clone());
+
+    let text: String = to_string(&json!([op])).unwrap();
+
+    assert_eq!(
+      from_value::<Patch>(json!([op])).unwrap_err().to_string(),
+      *message
+    );
+    assert!(from_str::<PatchRef<'_>>(&text).is_err());
+  }
+}
+
+#[test]
+fn test_serialize() {
+  let ops: Value = json!([
+    { "op": "test", "path": "/a", "value": 1, "not": true },
+    { "op": "test", "path": "/a", "type": "string" },
+    { "op": "test", "path": "/a", "exists": false },
+    { "op": "test", "path": "/a", "lt": 1.5 },
+    { "op": "test", "path": "/a", "gt": 2, "not": true },
+  ]);
+
+  let patch: Patch = patch(ops.clone());
+
+  assert_eq!(
+    patch.iter().nth(1).unwrap(),
+    &Operation::Test(OpTest {
+      path: "/a".into(),
+      value: Value::Null,
+      not: false,
+      condition: Some(Condition::Type(ValueType::String)),
+    })
+  );
+
+  assert_eq!(to_value(&patch).unwrap(), ops);
+
+  let text: String = to_string(&ops).unwrap();
+  let borrowed: PatchRef<'_> = from_str(&text).unwrap();
+
+  assert_eq!(to_value(&borrowed).unwrap(), ops);
+  assert_eq!(borrowed.to_patch().unwrap(), patch);
+}
@@ -0,0 +1,127 @@
+use json_patch::merge_strategic;
+use json_patch::MergeKeys;
+use json_patch::Pointer;
+use serde_json::json;
+use serde_json::Value;
+
+fn keys() -> MergeKeys {
+  MergeKeys::new()
+    .with(Pointer::new("/spec/containers").unwrap(), "name")
+    .with(
+      Pointer::new("/spec/containers/ports").unwrap(),
+      "containerPort",
+    )
+}
+
+#[test]
+fn test_keyed_lists() {
+  let mut value: Value = json!({
+    "spec": {
+      "containers": [
+        {"name": "app", "image": "app:1", "ports": [{"containerPort": 80}]},
+        {"name": "proxy", "image": "proxy:1"},
+      ],
+      "volumes": [{"name": "data"}],
+    }
+  });
+
+  let patch: Value = json!({
+    "spec": {
+      "containers": [
+        {"name": "app", "image": "app:2", "ports": [{"containerPort": 443}]},
+        {"name": "proxy", "$patch": "delete"},
+        {"name": "sidecar", "image": "sidecar:1", "env": null},
+      ],
+      "volumes": [{"name": "cache"}],
+    }
+  });
+
+  merge_strategic(&mut value, &patch, &keys()).unwrap();
+
+  assert_eq!(
+    value,
+    json!({
+      "spec": {
+        "containers": [
+          {"name": "app", "image": "app:2", "ports": [{"containerPort": 80}, {"containerPort": 443}]},
+          {"name": "sidecar", "image": "sidecar:1"},
+        ],
+        "volumes": [{"name": "cache"}],
+      }
+    })
+  );
+}
+
+#[test]
+fn test_directives() {
+  let mut value: Value = json!({
+    "spec": {
+      "containers": [{"name": "app", "image": "app:1"}, {"name": "proxy"}],
+      "selector": {"a": 1, "b": 2},
+      "strategy": {"type": "Recreate"},
+    }
+  });
+
+  let patch: Value = json!({
+    "spec": {
+      "containers": [{"$patch": "replace"}, {"name": "web"}],
+      "selector": {"$patch": "replace", "c": 3},
+      "strategy": {"$patch": "delete"},
+    }
+  });
+
+  merge_strategic(&mut value, &patch, &keys()).unwrap();
+
+  assert_eq!(
+    value,
+    json!({
+      "spec": {
+        "containers": [{"name": "web"}],
+        "selector": {"c": 3},
+      }
+    })
+  );
+
+  assert!(merge_strategic(&mut value, &json!({"$patch": "delete"}), &keys()).is_err());
+  assert!(merge_strategic(&mut value, &json!({"a": {"$patch": "spam"}}), &keys()).is_err());
+
+  let expected: Value = value.clone();
+
+  assert!(merge_strategic(
+    &mut value,
+    &json!({"spec": {"containers": [{"image": "app:3"}]}}),
+    &keys()
+  )
+  .is_err());
+  assert_eq!(value, expected);
+
+  assert!(merge_strategic(
+    &mut value,
+    &json!({"spec": {"containers": [{"name": "web", "image": "web:2"}, 1]}}),
+    &keys()
+  )
+  .is_err());
+  assert_eq!(value, expected);
+
+  assert!(merge_strategic(
+    &mut value,
+    &json!({"spec": {"containers": [{"name": "web", "$patch": "spam"}]}}),
+    &keys()
+  )
+  .is_err());
+  assert_eq!(value, expected);
+
+  assert!(merge_strategic(&mut value, &json!({"a": 2, "b": {"$patch": 5}}), &keys()).is_err());
+  assert_eq!(value, expected);
+
+  assert!(merge_strategic(
+    &mut value,
+    &json!({"a": 2, "spec": {"containers": [
+      {"name": "app", "image": "app:3"},
+      {"name": "web", "ports": [{"containerPort": 80, "$patch": 5}]},
+    ]}}),
+    &keys()
+  )
+  .is_err());
+  assert_eq!(value, expected);
+}
@@ -0,0 +1,93 @@
+use json_patch::Error;
+use json_patch::Patch;
+use json_patch::PatchReader;
+use serde_json::from_str;
+use serde_json::json;
+use serde_json::Value;
+use std::io::BufReader;
+
+const PATCH: &str = r#"[
+  { "op": "add", "path": "/a", "value": [1, 2] },
+  { "op": "copy", "from": "/a", "path": "/b" },
+  { "op": "remove", "path": "/a/0" },
+  { "op": "test", "path": "/b/1", "value": 2 }
+]"#;
+
+const FAILING: &str = r#"[
+  { "op": "add", "path": "/a", "value": 1 },
+  { "op": "test", "path": "/a", "value": 2 },
+  { "op": "add", "path": "/b", "value": 1 }
+]"#;
+
+#[test]
+fn test_stream() {
+  let mut value: Value = json!({ "x": true });
+  let reader: BufReader<&[u8]> = BufReader::with_capacity(1, PATCH.as_bytes());
+
+  assert_eq!(PatchReader::new(reader).apply_mut(&mut value).unwrap(), 4);
+
+  let patch: Patch = from_str(PATCH).unwrap();
+
+  assert_eq!(value, patch.apply_ref(&json!({ "x": true })).unwrap());
+}
+
+#[test]
+fn test_stream_empty() {
+  let mut value: Value = json!(1);
+
+  assert_eq!(
+    PatchReader::new(" [ ] ".as_bytes())
+      .apply_mut(&mut value)
+      .unwrap(),
+    0
+  );
+  assert_eq!(value, json!(1));
+}
+
+#[test]
+fn test_stream_atomic() {
+  let mut value: Value = json!({});
+
+  assert!(matches!(
+    PatchReader::new(FAILING.as_bytes()).apply_mut(&mut value),
+    Err(Error::InvalidTest)
+  ));
+  assert_eq!(value, json!({ "a": 1 }));
+
+  let mut value: Value = json!({});
+
+  assert!(matches!(
+    PatchReader::new(FAILING.as_bytes())
+      .atomic(true)
+      .apply_mut(&mut value),
+    Err(Error::InvalidTest)
+  ));
+  assert_eq!(value, json!({}));
+}
+
+#[test]
+fn test_stream_invalid() {
+  let invalid: &[&str] = &[
+    r#"[{ "op": "add", "path": "/a", "value": 1 }, { "op": "add" }]"#,
+    r#"[{ "op": "add", "path": "/a", "value": 1 }"#,
+    r#"[{ "op": "add", "path": "/a", "value": 1 }] []"#,
+    r#"{ "op": "add", "path": "/a", "value": 1 }"#,
+  ];
+
+  for input in invalid {
+    let mut value: Value = json!({});
+
+    assert!(matches!(
+      PatchReader::new(input.as_bytes()).apply_mut(&mut value),
+      Err(Error::InvalidPatch(_))
+    ));
+
+    let mut value: Value = json!({});
+
+    assert!(PatchReader::new(input.as_bytes())
+      .atomic(true)
+      .apply_mut(&mut value)
+      .is_err());
+    assert_eq!(value, json!({}));
+  }
+}
@@ -0,0 +1,149 @@
+mod fixtures;
+
+use json_patch::Error;
+use json_patch::Patch;
+use serde_json::from_str;
+use serde_json::from_value;
+use serde_json::json;
+use serde_json::to_string_pretty;
+use serde_json::Value;
+
+use self::fixtures::assert_same;
+use self::fixtures::fixtures;
+
+const DOCUMENT: &str = r#"{
+    "name":   "service",
+    "ports": [80,   443],
+    "env": {
+        "DEBUG": false
+    },
+    "empty": []
+}"#;
+
+#[test]
+fn test_formatting() {
+  let patch: Patch = from_value(json!([
+    {"op": "replace", "path": "/env/DEBUG", "value": true},
+    {"op": "add", "path": "/env/LEVEL", "value": "info"},
+    {"op": "add", "path": "/ports/1", "value": 8080},
+    {"op": "add", "path": "/empty/-", "value": {"a": 1}},
+  ]))
+  .unwrap();
+
+  assert_eq!(
+    patch.apply_text(DOCUMENT).unwrap(),
+    r#"{
+    "name":   "service",
+    "ports": [80,   8080,   443],
+    "env": {
+        "DEBUG": true,
+        "LEVEL": "info"
+    },
+    "empty": [{"a":1}]
+}"#
+  );
+}
+
+#[test]
+fn test_remove_and_move() {
+  let patch: Patch = from_value(json!([
+    {"op": "remove", "path": "/ports/0"},
+    {"op": "move", "from": "/env", "path": "/environment"},
+    {"op": "copy", "from": "/name", "path": "/empty/0"},
+    {"op": "test", "path": "/environment/DEBUG", "value": false},
+  ]))
+  .unwrap();
+
+  assert_eq!(
+    patch.apply_text(DOCUMENT).unwrap(),
+    r#"{
+    "name":   "service",
+    "ports": [443],
+    "empty": ["service"],
+    "environment": {
+        "DEBUG": false
+    }
+}"#
+  );
+}
+
+#[test]
+fn test_duplicate_keys() {
+  let text: &str = r#"{"a": 1, "b": { "c": 2 }, "a": 3}"#;
+
+  let patch: Patch = from_value(json!([
+    {"op": "test", "path": "/a", "value": 3},
+    {"op": "replace", "path": "/a", "value": 4},
+    {"op": "copy", "from": "/a", "path": "/b/d"},
+  ]))
+  .unwrap();
+
+  assert_eq!(
+    patch.apply_text(text).unwrap(),
+    r#"{"a": 1, "b": { "c": 2, "d": 4 }, "a": 4}"#
+  );
+
+  let patch: Patch = from_value(json!([
+    {"op": "move", "from": "/a", "path": "/b/a"},
+    {"op": "add", "path": "/b/c", "value": [5]},
+  ]))
+  .unwrap();
+
+  assert_eq!(
+    patch.apply_text(text).unwrap(),
+    r#"{"b": { "c": [5], "a": 3 }}"#
+  );
+}
+
+#[test]
+fn test_errors() {
+  let patch: Patch = from_value(json!([{"op": "remove", "path": "/missing"}])).unwrap();
+  assert!(matches!(
+    patch.apply_text(DOCUMENT),
+    Err(Error::InvalidPointer)
+  ));
+
+  let patch: Patch =
+    from_value(json!([{"op": "test", "path": "/name", "value": "other"}])).unwrap();
+  assert!(matches!(
+    patch.apply_text(DOCUMENT),
+    Err(Error::InvalidTest)
+  ));
+
+  let patch: Patch = from_value(json!([{"op": "add", "path": "/a", "value": 1}])).unwrap();
+  assert!(matches!(
+    patch.apply_text("{,}"),
+    Err(Error::InvalidValue { .. })
+  ));
+}
+
+#[test]
+fn test_scalar_root() {
+  let patch: Patch = from_value(json!([{"op": "add", "path": "/a", "value": 1}])).unwrap();
+
+  assert_eq!(patch.clone().apply_text("1").unwrap(), r#"{"a": 1}"#);
+  assert_eq!(
+    patch.clone().apply_text(" null\n").unwrap(),
+    " {\"a\": 1}\n"
+  );
+  assert_eq!(
+    from_str::<Value>(&patch.clone().apply_text("1").unwrap()).unwrap(),
+    patch.apply_ref(&json!(1)).unwrap()
+  );
+  assert_eq!(Patch::from(Vec::new()).apply_text("1").unwrap(), "1");
+}
+
+#[test]
+fn test_fixtures() {
+  for (doc, patch) in fixtures() {
+    let text: String = to_string_pretty(&doc).unwrap();
+
+    assert_same(
+      patch
+        .clone()
+        .apply_text(&text)
+        .map(|text| from_str::<Value>(&text).unwrap()),
+      patch.apply_ref(&doc),
+    );
+  }
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "toml")]
+
+use json_patch::merge_document;
+use json_patch::Error;
+use json_patch::Patch;
+use serde_json::from_value;
+use serde_json::json;
+
+#[test]
+fn test_toml() {
+  let mut value: toml::Value = toml::from_str(
+    r#"
+      [server]
+      host = "localhost"
+      port = 8080
+      started = 1979-05-27T07:32:00Z
+      tags = ["a", "b"]
+    "#,
+  )
+  .unwrap();
+
+  let patch: Patch = from_value(json!([
+    {"op": "test", "path": "/server/port", "value": 8080.0},
+    {"op": "test", "path": "/server/started", "value": "1979-05-27T07:32:00Z"},
+    {"op": "replace", "path": "/server/port", "value": 9090},
+    {"op": "add", "path": "/server/tags/1", "value": "c"},
+    {"op": "move", "from": "/server/host", "path": "/server/hostname"},
+  ]))
+  .unwrap();
+
+  patch.apply_mut(&mut value).unwrap();
+
+  let expected: toml::Value = toml::from_str(
+    r#"
+      [server]
+      hostname = "localhost"
+      port = 9090
+      started = 1979-05-27T07:32:00Z
+      tags = ["a", "c", "b"]
+    "#,
+  )
+  .unwrap();
+
+  assert_eq!(value, expected);
+
+  let patch: Patch =
+    from_value(json!([{"op": "add", "path": "/server/port", "value": null}])).unwrap();
+  assert!(matches!(
+    patch.apply_mut(&mut value),
+    Err(Error::UnsupportedValue)
+  ));
+
+  merge_document(
+    &mut value,
+    &json!({"server": {"port": null, "tls": {"enabled": true, "ca": null}}}),
+  )
+  .unwrap();
+
+  assert!(value["server"].get("port").is_none());
+  assert_eq!(
+    value["server"]["tls"],
+    toml::Value::try_from(json!({"enabled": true})).unwrap()
+  );
+}
@@ -0,0 +1,81 @@
+use json_patch::Error;
+use json_patch::MergePatch;
+use json_patch::Patch;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::from_value;
+use serde_json::json;
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+struct Settings {
+  name: String,
+  limits: Vec<Limit>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+struct Limit {
+  size: u32,
+}
+
+fn settings() -> Settings {
+  Settings {
+    name: "default".into(),
+    limits: vec![Limit { size: 1 }],
+  }
+}
+
+#[test]
+fn test_patch_typed() {
+  let mut value: Settings = settings();
+
+  let patch: Patch = from_value(json!([
+    {"op": "replace", "path": "/name", "value": "custom"},
+    {"op": "add", "path": "/limits/-", "value": {"size": 2}},
+  ]))
+  .unwrap();
+
+  patch.apply_to(&mut value).unwrap();
+
+  assert_eq!(value.name, "custom");
+  assert_eq!(value.limits, [Limit { size: 1 }, Limit { size: 2 }]);
+
+  let patch: Patch = from_value(json!([
+    {"op": "replace", "path": "/name", "value": "other"},
+    {"op": "replace", "path": "/limits/1/size", "value": "large"},
+  ]))
+  .unwrap();
+
+  match patch.apply_to(&mut value).unwrap_err() {
+    Error::InvalidValue { path, .. } => assert_eq!(path.as_str(), "/limits/1/size"),
+    error => panic!("Unexpected Error: {}", error),
+  }
+
+  assert_eq!(value.name, "custom");
+}
+
+#[test]
+fn test_merge_typed() {
+  let mut value: Settings = settings();
+
+  MergePatch::new(json!({"name": "custom"}))
+    .apply_to(&mut value)
+    .unwrap();
+
+  assert_eq!(value.name, "custom");
+
+  match MergePatch::new(json!({"name": null}))
+    .apply_to(&mut value)
+    .unwrap_err()
+  {
+    Error::InvalidValue { path, .. } => assert!(path.is_root()),
+    error => panic!("Unexpected Error: {}", error),
+  }
+
+  assert_eq!(
+    value,
+    Settings {
+      name: "custom".into(),
+      ..settings()
+    }
+  );
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "yaml")]
+
+use json_patch::merge_document;
+use json_patch::Patch;
+use serde_json::from_value;
+use serde_json::json;
+
+#[test]
+fn test_yaml() {
+  let mut value: serde_yaml::Value = serde_yaml::from_str(
+    "
+      service:
+        replicas: 2
+        ports: [80, 443]
+        owner: ~
+    ",
+  )
+  .unwrap();
+
+  let patch: Patch = from_value(json!([
+    {"op": "test", "path": "/service/owner", "value": null},
+    {"op": "replace", "path": "/service/replicas", "value": 3},
+    {"op": "remove", "path": "/service/ports/0"},
+    {"op": "add", "path": "/service/labels", "value": {"tier": "web"}},
+  ]))
+  .unwrap();
+
+  patch.apply_mut(&mut value).unwrap();
+  merge_document(&mut value, &json!({"service": {"owner": "ops"}})).unwrap();
+
+  let expected: serde_yaml::Value = serde_yaml::from_str(
+    "
+      service:
+        replicas: 3
+        ports: [443]
+        owner: ops
+        labels:
+          tier: web
+    ",
+  )
+  .unwrap();
+
+  assert_eq!(value, expected);
+}